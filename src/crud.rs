@@ -59,10 +59,401 @@
 ///   SignalTypes
 /// );
 /// ```
+/// An optional tenth argument sets the [ConflictStrategy](crate::retrieval::conflict_resolution::ConflictStrategy)
+/// used to pick the latest version of entries which have been updated concurrently.
+/// It defaults to `ConflictStrategy::LastWriterWins`.
+/// ```ignore
+/// crud!(
+///   Example,
+///   EntryTypes,
+///   EntryTypes::Example,
+///   LinkTypes,
+///   LinkTypes::All,
+///   example,
+///   "example",
+///   get_peers,
+///   SignalTypes,
+///   ConflictStrategy::FirstWriterWins
+/// );
+/// ```
+/// An optional eleventh argument sets the [Ownership](crate::modify_chain::ownership::Ownership) mode,
+/// which decides who can update and delete entries. It defaults to `Ownership::Anyone`.
+/// With `Ownership::AuthorOnly`, only the author of the original entry can update or delete it,
+/// and anyone else gets back a [CrudError::NotOwner](crate::errors::CrudError::NotOwner) error.
+/// ```ignore
+/// crud!(
+///   Example,
+///   EntryTypes,
+///   EntryTypes::Example,
+///   LinkTypes,
+///   LinkTypes::All,
+///   example,
+///   "example",
+///   get_peers,
+///   SignalTypes,
+///   ConflictStrategy::LastWriterWins,
+///   Ownership::AuthorOnly
+/// );
+/// ```
+/// The same can be done with named options, given in any order, of which only
+/// `entry_types`, `link_types` and `link_type` are required.
+/// ```ignore
+/// crud!(Example {
+///   entry_types: EntryTypes,
+///   link_types: LinkTypes,
+///   link_type: LinkTypes::All,
+///   signals: SignalTypes via get_peers,
+///   ops: [create, fetch, update],
+/// });
+/// ```
+/// The options, and their defaults, are:
+/// - `entry_types`: the EntryTypes enum of the zome
+/// - `entry_type`: the variant of `entry_types` holding this type, defaults to `EntryTypes::Example`
+/// - `link_types`: the LinkTypes enum of the zome
+/// - `link_type`: the variant of `link_types` used to link entries off of the Path
+/// - `name`: the name used in the zome functions, defaults to the type name in snake case, `example`
+/// - `path`: the Path to which all entries are linked, defaults to the `name` as a string, `"example"`
+/// - `signals`: `SignalTypes via get_peers` to send signals of every change to the peers returned by
+///   `get_peers`, or `none`, the default, to send no signals
+/// - `conflict_strategy`: defaults to `ConflictStrategy::LastWriterWins`
+/// - `ownership`: defaults to `Ownership::Anyone`
+/// - `get_options`: the GetOptions used to fetch, defaults to `GetOptions::network()`.
+///   Clients can override it per call by giving a GetStrategy along with their fetch
+/// - `ops`: which of `create`, `fetch`, `update`, `delete` and `history` to generate zome functions for,
+///   defaults to all of them. Leave out `history` to not expose `fetch_example_history`
+/// - `time_index`: `true` to also link every created entry off of a time path, under `"<path>_time"`,
+///   and every created or updated entry off of a time path under `"<path>_modified"`,
+///   and to generate the `fetch_examples_by_time`, `fetch_examples_in_time_range`,
+///   `fetch_examples_in_timestamp_range`, `fetch_examples_modified_in_time_range` and
+///   `fetch_examples_modified_in_timestamp_range` zome functions along with the other fetch functions.
+///   Defaults to `false`. The time paths are bucketed by the hour,
+///   unless a [TimeIndexGranularity](crate::datetime_queries::inputs::TimeIndexGranularity)
+///   is given instead of `true`, such as `time_index: Minute`
+/// - `signal_payload`: how much of a created or updated record the signals carry, one of the
+///   [SignalPayload](crate::signals::SignalPayload) variants `Full`, the default, `HashesOnly` or `Diff`
+/// - `local_signals`: `true` to also emit the signal of every change to the UIs of the agent who made it,
+///   independently of the `signals` sent to peers, so that many windows on one conductor stay in sync.
+///   Without `signals`, the emitted signals are plain [ActionSignal](crate::signals::ActionSignal)s.
+///   Defaults to `false`
 #[macro_export]
 macro_rules! crud {
+    /*
+      KEYWORD FORM
+    */
+
+    (
+      $crud_type:ident { $($options:tt)* }
+    ) => {
+        $crate::crud!(@options $crud_type
+          [] [] [] [] [] [] []
+          [$crate::retrieval::conflict_resolution::ConflictStrategy::LastWriterWins]
+          [$crate::modify_chain::ownership::Ownership::Anyone]
+          [::hdk::prelude::GetOptions::network()]
+          [create fetch update delete history]
+          [false]
+          [Full]
+          [false]
+          $($options)*
+        );
+    };
+
+    // collect the options, in any order, into the slots
+    // [entry_types] [entry_type] [link_types] [link_type] [name] [path] [signals]
+    // [conflict_strategy] [ownership] [get_options] [ops] [time_index] [signal_payload] [local_signals]
+    (
+      @options $crud_type:ident
+      [$($entry_types:tt)*] [$($entry_type:tt)*] [$($link_types:tt)*] [$($link_type:tt)*] [$($i:tt)*] [$($path:tt)*] [$($signals:tt)*]
+      [$($conflict_strategy:tt)*] [$($ownership:tt)*] [$($get_options:tt)*] [$($ops:tt)*] [$($time_index:tt)*] [$($signal_payload:tt)*] [$($local_signals:tt)*]
+      entry_types: $value:ident $(, $($rest:tt)*)?
+    ) => {
+        $crate::crud!(@options $crud_type
+          [$value] [$($entry_type)*] [$($link_types)*] [$($link_type)*] [$($i)*] [$($path)*] [$($signals)*]
+          [$($conflict_strategy)*] [$($ownership)*] [$($get_options)*] [$($ops)*] [$($time_index)*] [$($signal_payload)*] [$($local_signals)*]
+          $($($rest)*)?
+        );
+    };
+    (
+      @options $crud_type:ident
+      [$($entry_types:tt)*] [$($entry_type:tt)*] [$($link_types:tt)*] [$($link_type:tt)*] [$($i:tt)*] [$($path:tt)*] [$($signals:tt)*]
+      [$($conflict_strategy:tt)*] [$($ownership:tt)*] [$($get_options:tt)*] [$($ops:tt)*] [$($time_index:tt)*] [$($signal_payload:tt)*] [$($local_signals:tt)*]
+      entry_type: $value:expr $(, $($rest:tt)*)?
+    ) => {
+        $crate::crud!(@options $crud_type
+          [$($entry_types)*] [$value] [$($link_types)*] [$($link_type)*] [$($i)*] [$($path)*] [$($signals)*]
+          [$($conflict_strategy)*] [$($ownership)*] [$($get_options)*] [$($ops)*] [$($time_index)*] [$($signal_payload)*] [$($local_signals)*]
+          $($($rest)*)?
+        );
+    };
+    (
+      @options $crud_type:ident
+      [$($entry_types:tt)*] [$($entry_type:tt)*] [$($link_types:tt)*] [$($link_type:tt)*] [$($i:tt)*] [$($path:tt)*] [$($signals:tt)*]
+      [$($conflict_strategy:tt)*] [$($ownership:tt)*] [$($get_options:tt)*] [$($ops:tt)*] [$($time_index:tt)*] [$($signal_payload:tt)*] [$($local_signals:tt)*]
+      link_types: $value:ident $(, $($rest:tt)*)?
+    ) => {
+        $crate::crud!(@options $crud_type
+          [$($entry_types)*] [$($entry_type)*] [$value] [$($link_type)*] [$($i)*] [$($path)*] [$($signals)*]
+          [$($conflict_strategy)*] [$($ownership)*] [$($get_options)*] [$($ops)*] [$($time_index)*] [$($signal_payload)*] [$($local_signals)*]
+          $($($rest)*)?
+        );
+    };
+    (
+      @options $crud_type:ident
+      [$($entry_types:tt)*] [$($entry_type:tt)*] [$($link_types:tt)*] [$($link_type:tt)*] [$($i:tt)*] [$($path:tt)*] [$($signals:tt)*]
+      [$($conflict_strategy:tt)*] [$($ownership:tt)*] [$($get_options:tt)*] [$($ops:tt)*] [$($time_index:tt)*] [$($signal_payload:tt)*] [$($local_signals:tt)*]
+      link_type: $value:expr $(, $($rest:tt)*)?
+    ) => {
+        $crate::crud!(@options $crud_type
+          [$($entry_types)*] [$($entry_type)*] [$($link_types)*] [$value] [$($i)*] [$($path)*] [$($signals)*]
+          [$($conflict_strategy)*] [$($ownership)*] [$($get_options)*] [$($ops)*] [$($time_index)*] [$($signal_payload)*] [$($local_signals)*]
+          $($($rest)*)?
+        );
+    };
+    (
+      @options $crud_type:ident
+      [$($entry_types:tt)*] [$($entry_type:tt)*] [$($link_types:tt)*] [$($link_type:tt)*] [$($i:tt)*] [$($path:tt)*] [$($signals:tt)*]
+      [$($conflict_strategy:tt)*] [$($ownership:tt)*] [$($get_options:tt)*] [$($ops:tt)*] [$($time_index:tt)*] [$($signal_payload:tt)*] [$($local_signals:tt)*]
+      name: $value:ident $(, $($rest:tt)*)?
+    ) => {
+        $crate::crud!(@options $crud_type
+          [$($entry_types)*] [$($entry_type)*] [$($link_types)*] [$($link_type)*] [$value] [$($path)*] [$($signals)*]
+          [$($conflict_strategy)*] [$($ownership)*] [$($get_options)*] [$($ops)*] [$($time_index)*] [$($signal_payload)*] [$($local_signals)*]
+          $($($rest)*)?
+        );
+    };
+    (
+      @options $crud_type:ident
+      [$($entry_types:tt)*] [$($entry_type:tt)*] [$($link_types:tt)*] [$($link_type:tt)*] [$($i:tt)*] [$($path:tt)*] [$($signals:tt)*]
+      [$($conflict_strategy:tt)*] [$($ownership:tt)*] [$($get_options:tt)*] [$($ops:tt)*] [$($time_index:tt)*] [$($signal_payload:tt)*] [$($local_signals:tt)*]
+      path: $value:expr $(, $($rest:tt)*)?
+    ) => {
+        $crate::crud!(@options $crud_type
+          [$($entry_types)*] [$($entry_type)*] [$($link_types)*] [$($link_type)*] [$($i)*] [$value] [$($signals)*]
+          [$($conflict_strategy)*] [$($ownership)*] [$($get_options)*] [$($ops)*] [$($time_index)*] [$($signal_payload)*] [$($local_signals)*]
+          $($($rest)*)?
+        );
+    };
+    (
+      @options $crud_type:ident
+      [$($entry_types:tt)*] [$($entry_type:tt)*] [$($link_types:tt)*] [$($link_type:tt)*] [$($i:tt)*] [$($path:tt)*] [$($signals:tt)*]
+      [$($conflict_strategy:tt)*] [$($ownership:tt)*] [$($get_options:tt)*] [$($ops:tt)*] [$($time_index:tt)*] [$($signal_payload:tt)*] [$($local_signals:tt)*]
+      signals: none $(, $($rest:tt)*)?
+    ) => {
+        $crate::crud!(@options $crud_type
+          [$($entry_types)*] [$($entry_type)*] [$($link_types)*] [$($link_type)*] [$($i)*] [$($path)*] []
+          [$($conflict_strategy)*] [$($ownership)*] [$($get_options)*] [$($ops)*] [$($time_index)*] [$($signal_payload)*] [$($local_signals)*]
+          $($($rest)*)?
+        );
+    };
+    (
+      @options $crud_type:ident
+      [$($entry_types:tt)*] [$($entry_type:tt)*] [$($link_types:tt)*] [$($link_type:tt)*] [$($i:tt)*] [$($path:tt)*] [$($signals:tt)*]
+      [$($conflict_strategy:tt)*] [$($ownership:tt)*] [$($get_options:tt)*] [$($ops:tt)*] [$($time_index:tt)*] [$($signal_payload:tt)*] [$($local_signals:tt)*]
+      signals: $signal_type:ident via $get_peers:ident $(, $($rest:tt)*)?
+    ) => {
+        $crate::crud!(@options $crud_type
+          [$($entry_types)*] [$($entry_type)*] [$($link_types)*] [$($link_type)*] [$($i)*] [$($path)*] [$signal_type via $get_peers]
+          [$($conflict_strategy)*] [$($ownership)*] [$($get_options)*] [$($ops)*] [$($time_index)*] [$($signal_payload)*] [$($local_signals)*]
+          $($($rest)*)?
+        );
+    };
+    (
+      @options $crud_type:ident
+      [$($entry_types:tt)*] [$($entry_type:tt)*] [$($link_types:tt)*] [$($link_type:tt)*] [$($i:tt)*] [$($path:tt)*] [$($signals:tt)*]
+      [$($conflict_strategy:tt)*] [$($ownership:tt)*] [$($get_options:tt)*] [$($ops:tt)*] [$($time_index:tt)*] [$($signal_payload:tt)*] [$($local_signals:tt)*]
+      conflict_strategy: $value:expr $(, $($rest:tt)*)?
+    ) => {
+        $crate::crud!(@options $crud_type
+          [$($entry_types)*] [$($entry_type)*] [$($link_types)*] [$($link_type)*] [$($i)*] [$($path)*] [$($signals)*]
+          [$value] [$($ownership)*] [$($get_options)*] [$($ops)*] [$($time_index)*] [$($signal_payload)*] [$($local_signals)*]
+          $($($rest)*)?
+        );
+    };
+    (
+      @options $crud_type:ident
+      [$($entry_types:tt)*] [$($entry_type:tt)*] [$($link_types:tt)*] [$($link_type:tt)*] [$($i:tt)*] [$($path:tt)*] [$($signals:tt)*]
+      [$($conflict_strategy:tt)*] [$($ownership:tt)*] [$($get_options:tt)*] [$($ops:tt)*] [$($time_index:tt)*] [$($signal_payload:tt)*] [$($local_signals:tt)*]
+      ownership: $value:expr $(, $($rest:tt)*)?
+    ) => {
+        $crate::crud!(@options $crud_type
+          [$($entry_types)*] [$($entry_type)*] [$($link_types)*] [$($link_type)*] [$($i)*] [$($path)*] [$($signals)*]
+          [$($conflict_strategy)*] [$value] [$($get_options)*] [$($ops)*] [$($time_index)*] [$($signal_payload)*] [$($local_signals)*]
+          $($($rest)*)?
+        );
+    };
+    (
+      @options $crud_type:ident
+      [$($entry_types:tt)*] [$($entry_type:tt)*] [$($link_types:tt)*] [$($link_type:tt)*] [$($i:tt)*] [$($path:tt)*] [$($signals:tt)*]
+      [$($conflict_strategy:tt)*] [$($ownership:tt)*] [$($get_options:tt)*] [$($ops:tt)*] [$($time_index:tt)*] [$($signal_payload:tt)*] [$($local_signals:tt)*]
+      get_options: $value:expr $(, $($rest:tt)*)?
+    ) => {
+        $crate::crud!(@options $crud_type
+          [$($entry_types)*] [$($entry_type)*] [$($link_types)*] [$($link_type)*] [$($i)*] [$($path)*] [$($signals)*]
+          [$($conflict_strategy)*] [$($ownership)*] [$value] [$($ops)*] [$($time_index)*] [$($signal_payload)*] [$($local_signals)*]
+          $($($rest)*)?
+        );
+    };
+    (
+      @options $crud_type:ident
+      [$($entry_types:tt)*] [$($entry_type:tt)*] [$($link_types:tt)*] [$($link_type:tt)*] [$($i:tt)*] [$($path:tt)*] [$($signals:tt)*]
+      [$($conflict_strategy:tt)*] [$($ownership:tt)*] [$($get_options:tt)*] [$($ops:tt)*] [$($time_index:tt)*] [$($signal_payload:tt)*] [$($local_signals:tt)*]
+      ops: [$($op:ident),* $(,)?] $(, $($rest:tt)*)?
+    ) => {
+        $crate::crud!(@options $crud_type
+          [$($entry_types)*] [$($entry_type)*] [$($link_types)*] [$($link_type)*] [$($i)*] [$($path)*] [$($signals)*]
+          [$($conflict_strategy)*] [$($ownership)*] [$($get_options)*] [$($op)*] [$($time_index)*] [$($signal_payload)*] [$($local_signals)*]
+          $($($rest)*)?
+        );
+    };
+    (
+      @options $crud_type:ident
+      [$($entry_types:tt)*] [$($entry_type:tt)*] [$($link_types:tt)*] [$($link_type:tt)*] [$($i:tt)*] [$($path:tt)*] [$($signals:tt)*]
+      [$($conflict_strategy:tt)*] [$($ownership:tt)*] [$($get_options:tt)*] [$($ops:tt)*] [$($time_index:tt)*] [$($signal_payload:tt)*] [$($local_signals:tt)*]
+      time_index: $value:tt $(, $($rest:tt)*)?
+    ) => {
+        $crate::crud!(@options $crud_type
+          [$($entry_types)*] [$($entry_type)*] [$($link_types)*] [$($link_type)*] [$($i)*] [$($path)*] [$($signals)*]
+          [$($conflict_strategy)*] [$($ownership)*] [$($get_options)*] [$($ops)*] [$value] [$($signal_payload)*] [$($local_signals)*]
+          $($($rest)*)?
+        );
+    };
+    (
+      @options $crud_type:ident
+      [$($entry_types:tt)*] [$($entry_type:tt)*] [$($link_types:tt)*] [$($link_type:tt)*] [$($i:tt)*] [$($path:tt)*] [$($signals:tt)*]
+      [$($conflict_strategy:tt)*] [$($ownership:tt)*] [$($get_options:tt)*] [$($ops:tt)*] [$($time_index:tt)*] [$($signal_payload:tt)*] [$($local_signals:tt)*]
+      signal_payload: $value:ident $(, $($rest:tt)*)?
+    ) => {
+        $crate::crud!(@options $crud_type
+          [$($entry_types)*] [$($entry_type)*] [$($link_types)*] [$($link_type)*] [$($i)*] [$($path)*] [$($signals)*]
+          [$($conflict_strategy)*] [$($ownership)*] [$($get_options)*] [$($ops)*] [$($time_index)*] [$value] [$($local_signals)*]
+          $($($rest)*)?
+        );
+    };
+    (
+      @options $crud_type:ident
+      [$($entry_types:tt)*] [$($entry_type:tt)*] [$($link_types:tt)*] [$($link_type:tt)*] [$($i:tt)*] [$($path:tt)*] [$($signals:tt)*]
+      [$($conflict_strategy:tt)*] [$($ownership:tt)*] [$($get_options:tt)*] [$($ops:tt)*] [$($time_index:tt)*] [$($signal_payload:tt)*] [$($local_signals:tt)*]
+      local_signals: $value:tt $(, $($rest:tt)*)?
+    ) => {
+        $crate::crud!(@options $crud_type
+          [$($entry_types)*] [$($entry_type)*] [$($link_types)*] [$($link_type)*] [$($i)*] [$($path)*] [$($signals)*]
+          [$($conflict_strategy)*] [$($ownership)*] [$($get_options)*] [$($ops)*] [$($time_index)*] [$($signal_payload)*] [$value]
+          $($($rest)*)?
+        );
+    };
+    // all of the options have been collected
+    (
+      @options $crud_type:ident
+      $([$($slot:tt)*])*
+    ) => {
+        $crate::crud!(@defaults $crud_type $([$($slot)*])*);
+    };
+    (
+      @options $crud_type:ident
+      $([$($slot:tt)*])*
+      $key:ident $($rest:tt)*
+    ) => {
+        compile_error!(concat!("crud! got an unknown or invalid option `", stringify!($key), "`"));
+    };
+
+    // fill in the defaults which depend on other options
+    (
+      @defaults $crud_type:ident
+      [] $($slots:tt)*
+    ) => {
+        compile_error!("crud! needs the `entry_types` option");
+    };
+    (
+      @defaults $crud_type:ident
+      [$entry_types:ident] [$($entry_type:tt)*] [] $($slots:tt)*
+    ) => {
+        compile_error!("crud! needs the `link_types` option");
+    };
+    (
+      @defaults $crud_type:ident
+      [$entry_types:ident] [$($entry_type:tt)*] [$link_types:ident] [] $($slots:tt)*
+    ) => {
+        compile_error!("crud! needs the `link_type` option");
+    };
+    (
+      @defaults $crud_type:ident
+      [$entry_types:ident] [] $($slots:tt)*
+    ) => {
+        // the variant of the EntryTypes enum is expected to share the name of the entry type
+        $crate::crud!(@defaults $crud_type [$entry_types] [$entry_types::$crud_type] $($slots)*);
+    };
+    (
+      @defaults $crud_type:ident
+      [$entry_types:ident] [$entry_type:expr] [$link_types:ident] [$link_type:expr] [] $($slots:tt)*
+    ) => {
+        ::paste::paste! {
+          $crate::crud!(@defaults $crud_type [$entry_types] [$entry_type] [$link_types] [$link_type] [[<$crud_type:snake>]] $($slots)*);
+        }
+    };
+    (
+      @defaults $crud_type:ident
+      [$entry_types:ident] [$entry_type:expr] [$link_types:ident] [$link_type:expr] [$i:ident] [] $($slots:tt)*
+    ) => {
+        $crate::crud!(@defaults $crud_type [$entry_types] [$entry_type] [$link_types] [$link_type] [$i] [stringify!($i)] $($slots)*);
+    };
+    (
+      @defaults $crud_type:ident
+      [$entry_types:ident] [$entry_type:expr] [$link_types:ident] [$link_type:expr] [$i:ident] [$path:expr] [$signal_type:ident via $get_peers:ident]
+      [$conflict_strategy:expr] [$ownership:expr] [$get_options:expr] [$($op:ident)*] [$time_index:tt] [$signal_payload:ident] [$local_signals:tt]
+    ) => {
+        $crate::crud!(@build
+          $crud_type, $entry_types, $entry_type, $link_types, $link_type, $i, $path, $signal_type, Some($get_peers()?),
+          $conflict_strategy, $ownership, $get_options, $time_index, $signal_payload, $local_signals, [$($op)*]
+        );
+    };
+    (
+      @defaults $crud_type:ident
+      [$entry_types:ident] [$entry_type:expr] [$link_types:ident] [$link_type:expr] [$i:ident] [$path:expr] []
+      [$conflict_strategy:expr] [$ownership:expr] [$get_options:expr] [$($op:ident)*] [$time_index:tt] [$signal_payload:ident] [$local_signals:tt]
+    ) => {
+        // without signals, there is no need for a zome level signal type
+        $crate::crud!(@build
+          $crud_type, $entry_types, $entry_type, $link_types, $link_type, $i, $path, $crate::signals::ActionSignal<$crud_type>, None,
+          $conflict_strategy, $ownership, $get_options, $time_index, $signal_payload, $local_signals, [$($op)*]
+        );
+    };
+
+    /*
+      POSITIONAL FORM
+    */
+
     (
       $crud_type:ident, $entry_types:ident, $entry_type:expr, $link_types:ident, $link_type:expr, $i:ident, $path:expr, $get_peers:ident, $signal_type:ident
+    ) => {
+        $crate::crud!(
+          $crud_type, $entry_types, $entry_type, $link_types, $link_type, $i, $path, $get_peers, $signal_type,
+          $crate::retrieval::conflict_resolution::ConflictStrategy::LastWriterWins
+        );
+    };
+    (
+      $crud_type:ident, $entry_types:ident, $entry_type:expr, $link_types:ident, $link_type:expr, $i:ident, $path:expr, $get_peers:ident, $signal_type:ident, $conflict_strategy:expr
+    ) => {
+        $crate::crud!(
+          $crud_type, $entry_types, $entry_type, $link_types, $link_type, $i, $path, $get_peers, $signal_type, $conflict_strategy,
+          $crate::modify_chain::ownership::Ownership::Anyone
+        );
+    };
+    (
+      $crud_type:ident, $entry_types:ident, $entry_type:expr, $link_types:ident, $link_type:expr, $i:ident, $path:expr, $get_peers:ident, $signal_type:ident, $conflict_strategy:expr, $ownership:expr
+    ) => {
+        $crate::crud!(@build
+          $crud_type, $entry_types, $entry_type, $link_types, $link_type, $i, $path, $signal_type, Some($get_peers()?),
+          $conflict_strategy, $ownership, ::hdk::prelude::GetOptions::network(), false, Full, false, [create fetch update delete history]
+        );
+    };
+
+    /*
+      GENERATION
+    */
+
+    (
+      @build
+      $crud_type:ident, $entry_types:ident, $entry_type:expr, $link_types:ident, $link_type:expr, $i:ident, $path:expr, $signal_type:ty, $peers:expr,
+      $conflict_strategy:expr, $ownership:expr, $get_options:expr, $time_index:tt, $signal_payload:ident, $local_signals:tt, [$($op:ident)*]
     ) => {
         ::paste::paste! {
 
@@ -80,15 +471,22 @@ macro_rules! crud {
           {
             Path::from([<$i:upper _PATH>]).typed(link_type)
           }
+        }
 
-          #[doc ="This is what is expected by a call to [update_" $path "]"]
-          #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, SerializedBytes)]
-          #[serde(rename_all = "camelCase")]
-          pub struct [<$crud_type UpdateInput>] {
-            pub entry: $crud_type,
-            pub action_hash: ::holo_hash::ActionHashB64,
-          }
+        $(
+          $crate::crud!(@ $op
+            $crud_type, $entry_types, $entry_type, $link_types, $link_type, $i, $path, $signal_type, $peers,
+            $conflict_strategy, $ownership, $get_options, $time_index, $signal_payload, $local_signals
+          );
+        )*
+    };
 
+    (
+      @create
+      $crud_type:ident, $entry_types:ident, $entry_type:expr, $link_types:ident, $link_type:expr, $i:ident, $path:expr, $signal_type:ty, $peers:expr,
+      $conflict_strategy:expr, $ownership:expr, $get_options:expr, $time_index:tt, $signal_payload:ident, $local_signals:tt
+    ) => {
+        ::paste::paste! {
           /*
             CREATE
           */
@@ -109,37 +507,316 @@ macro_rules! crud {
               Some($crate::modify_chain::do_create::TypedPathOrEntryHash::TypedPath([< get_ $i _path >]($link_type)?)),
               $path.to_string(),
               $link_type,
-              Some($get_peers()?),
-              None,
+              $peers,
+              None, // send_signal_with_secrets
+              $crate::crud!(@time_paths [Created Modified] $time_index, $path),
+              $crate::signals::SignalPayload::$signal_payload,
+              $local_signals,
+            )
+          }
+
+          #[cfg(not(feature = "exclude_zome_fns"))]
+          /// This is the exposed/public Zome function for creating many entries of this type at once.
+          #[doc="Each entry will be created and linked off the main Path, just like [create_" $i "] would."]
+          /// It will send a single signal of this event
+          /// to all peers returned by the `get_peers` call given during the macro call to `crud!`
+          #[hdk_extern]
+          pub fn [<create_ $i s>](entries: Vec<$crud_type>) -> ExternResult<Vec<$crate::wire_record::WireRecord<[<$crud_type>]>>> {
+            let do_create = $crate::modify_chain::do_create::DoCreate {};
+            // wrap each in its EntryTypes variant
+            let entries = entries
+              .into_iter()
+              .map(|entry| ($entry_type(entry.clone()), entry))
+              .collect();
+            do_create.do_create_batch::<$entry_types, $crud_type, ::hdk::prelude::WasmError, $signal_type, $link_types> (
+              entries,
+              Some($crate::modify_chain::do_create::TypedPathOrEntryHash::TypedPath([< get_ $i _path >]($link_type)?)),
+              $path.to_string(),
+              $link_type,
+              $peers,
+              None, // send_signal_with_secrets
+              $crate::crud!(@time_paths [Created Modified] $time_index, $path),
+              $crate::signals::SignalPayload::$signal_payload,
+              $local_signals,
             )
           }
+        }
+    };
 
+    (
+      @fetch
+      $crud_type:ident, $entry_types:ident, $entry_type:expr, $link_types:ident, $link_type:expr, $i:ident, $path:expr, $signal_type:ty, $peers:expr,
+      $conflict_strategy:expr, $ownership:expr, $get_options:expr, $time_index:tt, $signal_payload:ident, $local_signals:tt
+    ) => {
+        ::paste::paste! {
           /*
             READ
           */
 
           #[cfg(not(feature = "exclude_zome_fns"))]
           /// This is the exposed/public Zome function for either fetching ALL or a SPECIFIC list of the entries of the type.
+          /// The FetchOptions can be given along with a GetStrategy, to fetch only what this agent holds locally.
           /// No signals will be sent as a result of calling this.
           /// Notice that it pluralizes the value of `$i`, the second argument to the crud! macro call.
           #[hdk_extern]
-          pub fn [<fetch_ $i s>](fetch_options: $crate::retrieval::inputs::FetchOptions) -> ExternResult<Vec<$crate::wire_record::WireRecord<[<$crud_type>]>>> {
+          pub fn [<fetch_ $i s>](fetch_input: $crate::retrieval::inputs::FetchInput) -> ExternResult<Vec<$crate::wire_record::WireRecord<[<$crud_type>]>>> {
             let do_fetch = $crate::modify_chain::do_fetch::DoFetch {};
             let fetch_entries = $crate::retrieval::fetch_entries::FetchEntries {};
             let fetch_links = $crate::retrieval::fetch_links::FetchLinks {};
-            let get_latest = $crate::retrieval::get_latest_for_entry::GetLatestEntry {};
+            let get_latest = $crate::retrieval::get_latest_for_entry::GetLatestEntry::new($conflict_strategy);
+            let (fetch_options, get_options) = fetch_input.into_parts($get_options);
             let link_type_filter = LinkTypeFilter::try_from($link_type)?;
             do_fetch.do_fetch::<$crud_type, ::hdk::prelude::WasmError>(
                 &fetch_entries,
                 &fetch_links,
                 &get_latest,
                 fetch_options,
-                GetOptions::network(),
+                get_options,
+                link_type_filter,
+                None, // link_tag
+                [< get_ $i _path >]($link_type)?,
+            )
+          }
+
+          #[cfg(not(feature = "exclude_zome_fns"))]
+          #[doc="This is just like [fetch_" $i "s], but it also returns the hashes of the entries which could not be fetched, and why."]
+          /// No signals will be sent as a result of calling this.
+          #[hdk_extern]
+          pub fn [<fetch_ $i s_report>](fetch_input: $crate::retrieval::inputs::FetchInput) -> ExternResult<$crate::retrieval::outputs::FetchReport<[<$crud_type>]>> {
+            let do_fetch = $crate::modify_chain::do_fetch::DoFetch {};
+            let fetch_entries = $crate::retrieval::fetch_entries::FetchEntries {};
+            let fetch_links = $crate::retrieval::fetch_links::FetchLinks {};
+            let get_latest = $crate::retrieval::get_latest_for_entry::GetLatestEntry::new($conflict_strategy);
+            let (fetch_options, get_options) = fetch_input.into_parts($get_options);
+            let link_type_filter = LinkTypeFilter::try_from($link_type)?;
+            do_fetch.do_fetch_report::<$crud_type, ::hdk::prelude::WasmError>(
+                &fetch_entries,
+                &fetch_links,
+                &get_latest,
+                fetch_options,
+                get_options,
+                link_type_filter,
+                None, // link_tag
+                [< get_ $i _path >]($link_type)?,
+            )
+          }
+
+          #[cfg(not(feature = "exclude_zome_fns"))]
+          /// This is the exposed/public Zome function for fetching the entries of the type one page at a time.
+          /// Pass the `next` cursor of a page as `after` to fetch the page following it.
+          /// No signals will be sent as a result of calling this.
+          #[hdk_extern]
+          pub fn [<fetch_ $i s_page>](page: $crate::retrieval::inputs::FetchPageInput) -> ExternResult<$crate::retrieval::outputs::FetchPage<[<$crud_type>]>> {
+            let do_fetch = $crate::modify_chain::do_fetch::DoFetch {};
+            let fetch_entries = $crate::retrieval::fetch_entries::FetchEntries {};
+            let fetch_links = $crate::retrieval::fetch_links::FetchLinks {};
+            let get_latest = $crate::retrieval::get_latest_for_entry::GetLatestEntry::new($conflict_strategy);
+            let get_options = page
+              .get_strategy
+              .map($crate::retrieval::utils::get_options_for)
+              .unwrap_or($get_options);
+            let link_type_filter = LinkTypeFilter::try_from($link_type)?;
+            do_fetch.do_fetch_page::<$crud_type, ::hdk::prelude::WasmError>(
+                &fetch_entries,
+                &fetch_links,
+                &get_latest,
+                page,
+                get_options,
                 link_type_filter,
                 None, // link_tag
                 [< get_ $i _path >]($link_type)?,
             )
           }
+        }
+
+        $crate::crud!(@fetch_by_time $time_index,
+          $crud_type, $link_types, $link_type, $i, $path, $conflict_strategy, $get_options
+        );
+    };
+
+    (
+      @history
+      $crud_type:ident, $entry_types:ident, $entry_type:expr, $link_types:ident, $link_type:expr, $i:ident, $path:expr, $signal_type:ty, $peers:expr,
+      $conflict_strategy:expr, $ownership:expr, $get_options:expr, $time_index:tt, $signal_payload:ident, $local_signals:tt
+    ) => {
+        ::paste::paste! {
+          #[cfg(not(feature = "exclude_zome_fns"))]
+          /// This is the exposed/public Zome function for fetching every version of a SPECIFIC entry of the type,
+          /// from the original to the latest update, in chronological order.
+          /// Pass it the `action_hash` of the original entry.
+          /// No signals will be sent as a result of calling this.
+          #[hdk_extern]
+          pub fn [<fetch_ $i _history>](action_hash: ::holo_hash::ActionHashB64) -> ExternResult<Vec<$crate::wire_record::Revision<[<$crud_type>]>>> {
+            let do_fetch = $crate::modify_chain::do_fetch::DoFetch {};
+            let get_history = $crate::retrieval::get_history_for_entry::GetHistory {};
+            do_fetch.do_fetch_history::<$crud_type, ::hdk::prelude::WasmError>(
+                &get_history,
+                action_hash,
+                $get_options,
+            )
+          }
+        }
+    };
+
+    (@time_paths $kinds:tt false, $path:expr) => {
+        vec![]
+    };
+    (@time_paths [$($kind:ident)*] $granularity:tt, $path:expr) => {
+        vec![$($crate::crud!(@time_index $kind $granularity, $path)),*]
+    };
+
+    (@time_index $kind:ident true, $path:expr) => {
+        $crate::crud!(@time_index $kind Hour, $path)
+    };
+    (@time_index $kind:ident $granularity:ident, $path:expr) => {
+        // separate trees from the Path of the type, so that the time paths
+        // don't show up among the entries linked off of it
+        $crate::datetime_queries::inputs::TimeIndex {
+          base_component: $crate::datetime_queries::inputs::TimeIndexKind::$kind.base_component(&$path.to_string()),
+          granularity: $crate::datetime_queries::inputs::TimeIndexGranularity::$granularity,
+        }
+    };
+
+    (
+      @fetch_by_time false,
+      $crud_type:ident, $link_types:ident, $link_type:expr, $i:ident, $path:expr, $conflict_strategy:expr, $get_options:expr
+    ) => {};
+    (
+      @fetch_by_time $time_index:tt,
+      $crud_type:ident, $link_types:ident, $link_type:expr, $i:ident, $path:expr, $conflict_strategy:expr, $get_options:expr
+    ) => {
+        ::paste::paste! {
+          #[cfg(not(feature = "exclude_zome_fns"))]
+          /// This is the exposed/public Zome function for fetching the entries of the type
+          /// which were created during a specific day, or hour of a day if `hour` is given.
+          /// No signals will be sent as a result of calling this.
+          #[hdk_extern]
+          pub fn [<fetch_ $i s_by_time>](input: $crate::datetime_queries::inputs::FetchEntriesTimeInput) -> ExternResult<Vec<$crate::wire_record::WireRecord<[<$crud_type>]>>> {
+            let fetchers = $crate::datetime_queries::fetchers::Fetchers {
+              get_latest: $crate::retrieval::get_latest_for_entry::GetLatestEntry::new($conflict_strategy),
+              ..$crate::datetime_queries::fetchers::Fetchers::default()
+            };
+            let link_type_filter = LinkTypeFilter::try_from($link_type)?;
+            $crate::datetime_queries::fetch_by_time::fetch_entries_by_time::<$crud_type, $link_types, ::hdk::prelude::WasmError>(
+                &fetchers.day,
+                &fetchers.hour,
+                &fetchers.get_latest,
+                link_type_filter,
+                $link_type,
+                input.time,
+                input.get_strategy.unwrap_or($get_options.strategy),
+                $crate::datetime_queries::inputs::TimeIndexKind::Created.base_component(&$path.to_string()),
+            )
+          }
+
+          #[cfg(not(feature = "exclude_zome_fns"))]
+          /// This is the exposed/public Zome function for fetching the entries of the type
+          /// which were created between `start_time` and the end of `end_time`, in the local time at `utc_offset`.
+          /// They are sorted by when they were created, in the given `order`, and at most `limit` of them are returned.
+          /// No signals will be sent as a result of calling this.
+          #[hdk_extern]
+          pub fn [<fetch_ $i s_in_time_range>](range: $crate::datetime_queries::inputs::FetchEntriesTimeRange) -> ExternResult<Vec<$crate::wire_record::WireRecord<[<$crud_type>]>>> {
+            let fetchers = $crate::datetime_queries::fetchers::Fetchers {
+              get_latest: $crate::retrieval::get_latest_for_entry::GetLatestEntry::new($conflict_strategy),
+              ..$crate::datetime_queries::fetchers::Fetchers::default()
+            };
+            let link_type_filter = LinkTypeFilter::try_from($link_type)?;
+            $crate::datetime_queries::fetch_in_time_range::fetch_entries_in_local_time_range::<$crud_type, $link_types, ::hdk::prelude::WasmError>(
+                &fetchers,
+                link_type_filter,
+                $link_type,
+                range,
+                $crate::datetime_queries::inputs::TimeIndexKind::Created,
+                $crate::crud!(@time_index Created $time_index, $path),
+                $get_options,
+            )
+          }
+
+          #[cfg(not(feature = "exclude_zome_fns"))]
+          /// This is the exposed/public Zome function for fetching the entries of the type
+          /// which were created from the `start` timestamp up to, but not including, the `end` timestamp.
+          /// They are sorted by when they were created, in the given `order`, and at most `limit` of them are returned.
+          /// No signals will be sent as a result of calling this.
+          #[hdk_extern]
+          pub fn [<fetch_ $i s_in_timestamp_range>](range: $crate::datetime_queries::inputs::FetchEntriesTimestampRange) -> ExternResult<Vec<$crate::wire_record::WireRecord<[<$crud_type>]>>> {
+            let fetchers = $crate::datetime_queries::fetchers::Fetchers {
+              get_latest: $crate::retrieval::get_latest_for_entry::GetLatestEntry::new($conflict_strategy),
+              ..$crate::datetime_queries::fetchers::Fetchers::default()
+            };
+            let link_type_filter = LinkTypeFilter::try_from($link_type)?;
+            $crate::datetime_queries::fetch_in_time_range::fetch_entries_in_timestamp_range::<$crud_type, $link_types, ::hdk::prelude::WasmError>(
+                &fetchers,
+                link_type_filter,
+                $link_type,
+                range,
+                $crate::datetime_queries::inputs::TimeIndexKind::Created,
+                $crate::crud!(@time_index Created $time_index, $path),
+                $get_options,
+            )
+          }
+
+          #[cfg(not(feature = "exclude_zome_fns"))]
+          /// This is the exposed/public Zome function for fetching the entries of the type
+          /// which were created or updated between `start_time` and the end of `end_time`, in the local time at `utc_offset`.
+          /// They are sorted by when they were last modified, in the given `order`, and at most `limit` of them are returned.
+          /// No signals will be sent as a result of calling this.
+          #[hdk_extern]
+          pub fn [<fetch_ $i s_modified_in_time_range>](range: $crate::datetime_queries::inputs::FetchEntriesTimeRange) -> ExternResult<Vec<$crate::wire_record::WireRecord<[<$crud_type>]>>> {
+            let fetchers = $crate::datetime_queries::fetchers::Fetchers {
+              get_latest: $crate::retrieval::get_latest_for_entry::GetLatestEntry::new($conflict_strategy),
+              ..$crate::datetime_queries::fetchers::Fetchers::default()
+            };
+            let link_type_filter = LinkTypeFilter::try_from($link_type)?;
+            $crate::datetime_queries::fetch_in_time_range::fetch_entries_in_local_time_range::<$crud_type, $link_types, ::hdk::prelude::WasmError>(
+                &fetchers,
+                link_type_filter,
+                $link_type,
+                range,
+                $crate::datetime_queries::inputs::TimeIndexKind::Modified,
+                $crate::crud!(@time_index Modified $time_index, $path),
+                $get_options,
+            )
+          }
+
+          #[cfg(not(feature = "exclude_zome_fns"))]
+          /// This is the exposed/public Zome function for fetching the entries of the type
+          /// which were created or updated from the `start` timestamp up to, but not including, the `end` timestamp,
+          /// such as everything that changed since a client last synced.
+          /// They are sorted by when they were last modified, in the given `order`, and at most `limit` of them are returned.
+          /// No signals will be sent as a result of calling this.
+          #[hdk_extern]
+          pub fn [<fetch_ $i s_modified_in_timestamp_range>](range: $crate::datetime_queries::inputs::FetchEntriesTimestampRange) -> ExternResult<Vec<$crate::wire_record::WireRecord<[<$crud_type>]>>> {
+            let fetchers = $crate::datetime_queries::fetchers::Fetchers {
+              get_latest: $crate::retrieval::get_latest_for_entry::GetLatestEntry::new($conflict_strategy),
+              ..$crate::datetime_queries::fetchers::Fetchers::default()
+            };
+            let link_type_filter = LinkTypeFilter::try_from($link_type)?;
+            $crate::datetime_queries::fetch_in_time_range::fetch_entries_in_timestamp_range::<$crud_type, $link_types, ::hdk::prelude::WasmError>(
+                &fetchers,
+                link_type_filter,
+                $link_type,
+                range,
+                $crate::datetime_queries::inputs::TimeIndexKind::Modified,
+                $crate::crud!(@time_index Modified $time_index, $path),
+                $get_options,
+            )
+          }
+        }
+    };
+
+    (
+      @update
+      $crud_type:ident, $entry_types:ident, $entry_type:expr, $link_types:ident, $link_type:expr, $i:ident, $path:expr, $signal_type:ty, $peers:expr,
+      $conflict_strategy:expr, $ownership:expr, $get_options:expr, $time_index:tt, $signal_payload:ident, $local_signals:tt
+    ) => {
+        ::paste::paste! {
+          #[doc ="This is what is expected by a call to [update_" $i "]"]
+          #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, SerializedBytes)]
+          #[serde(rename_all = "camelCase")]
+          pub struct [<$crud_type UpdateInput>] {
+            pub entry: $crud_type,
+            pub action_hash: ::holo_hash::ActionHashB64,
+          }
 
           /*
             UPDATE
@@ -158,11 +835,49 @@ macro_rules! crud {
               update.action_hash,
               $path.to_string(),
               $link_type,
-              Some($get_peers()?),
-              None,
+              $peers,
+              None, // send_signal_with_secrets
+              $crate::crud!(@time_paths [Modified] $time_index, $path),
+              $ownership,
+              $crate::signals::SignalPayload::$signal_payload,
+              $local_signals,
+              $get_options,
+            )
+          }
+
+          #[cfg(not(feature = "exclude_zome_fns"))]
+          /// This is the exposed/public Zome function for updating many entries of this type at once.
+          /// It will send a single signal of this event
+          /// to all peers returned by the `get_peers` call given during the macro call to `crud!`
+          #[hdk_extern]
+          pub fn [<update_ $i s>](updates: Vec<[<$crud_type UpdateInput>]>) -> ExternResult<Vec<$crate::wire_record::WireRecord<[<$crud_type>]>>> {
+            let do_update = $crate::modify_chain::do_update::DoUpdate {};
+            let updates = updates
+              .into_iter()
+              .map(|update| (update.entry, update.action_hash))
+              .collect();
+            do_update.do_update_batch::<$crud_type, ::hdk::prelude::WasmError, $signal_type, $link_types>(
+              updates,
+              $path.to_string(),
+              $link_type,
+              $peers,
+              None, // send_signal_with_secrets
+              $crate::crud!(@time_paths [Modified] $time_index, $path),
+              $ownership,
+              $crate::signals::SignalPayload::$signal_payload,
+              $local_signals,
+              $get_options,
             )
           }
+        }
+    };
 
+    (
+      @delete
+      $crud_type:ident, $entry_types:ident, $entry_type:expr, $link_types:ident, $link_type:expr, $i:ident, $path:expr, $signal_type:ty, $peers:expr,
+      $conflict_strategy:expr, $ownership:expr, $get_options:expr, $time_index:tt, $signal_payload:ident, $local_signals:tt
+    ) => {
+        ::paste::paste! {
           /*
             DELETE
           */
@@ -179,7 +894,28 @@ macro_rules! crud {
             do_delete.do_delete::<$crud_type, ::hdk::prelude::WasmError, $signal_type>(
               address,
               $path.to_string(),
-              Some($get_peers()?),
+              $peers,
+              None, // send_signal_with_secrets
+              $ownership,
+              $local_signals,
+            )
+          }
+
+          #[cfg(not(feature = "exclude_zome_fns"))]
+          /// This is the exposed/public Zome function for archiving many entries of this type at once.
+          #[doc="They will no longer be returned by [fetch_" $i "s]."]
+          /// It will send a single signal of this event
+          /// to all peers returned by the `get_peers` call given during the macro call to `crud!`
+          #[hdk_extern]
+          pub fn [<delete_ $i s>](addresses: Vec<::holo_hash::ActionHashB64>) -> ExternResult<Vec<::holo_hash::ActionHashB64>> {
+            let do_delete = $crate::modify_chain::do_delete::DoDelete {};
+            do_delete.do_delete_batch::<$crud_type, ::hdk::prelude::WasmError, $signal_type>(
+              addresses,
+              $path.to_string(),
+              $peers,
+              None, // send_signal_with_secrets
+              $ownership,
+              $local_signals,
             )
           }
         }
@@ -191,9 +927,9 @@ macro_rules! crud {
 /// as what you'll get back out of it.
 /// Anything that says "NOT GENERATED" is not
 /// generated by the crud! macro call, and the rest is.
-/// It will generate 4 public Zome functions
-/// The 4 Zome functions in this example would be:
-/// [create_example](example::create_example), [fetch_examples](example::fetch_examples), [update_example](example::update_example), and [delete_example](example::delete_example).
+/// It will generate 10 public Zome functions
+/// The 10 Zome functions in this example would be:
+/// [create_example](example::create_example), [create_examples](example::create_examples), [fetch_examples](example::fetch_examples), [fetch_examples_report](example::fetch_examples_report), [fetch_examples_page](example::fetch_examples_page), [fetch_example_history](example::fetch_example_history), [update_example](example::update_example), [update_examples](example::update_examples), [delete_example](example::delete_example), and [delete_examples](example::delete_examples).
 #[cfg(not(feature = "no_example"))]
 pub mod example {
     use crate::signals::*;
@@ -208,12 +944,31 @@ pub mod example {
         pub number: i32,
     }
 
+    /// NOT GENERATED
+    /// The entry types used by the keyword form of the crud! macro
+    /// in [keyword_example](super::keyword_example)
+    #[hdk_entry_helper]
+    #[derive(Clone, PartialEq)]
+    pub struct Note {
+        pub text: String,
+    }
+
+    /// NOT GENERATED
+    #[hdk_entry_helper]
+    #[derive(Clone, PartialEq)]
+    pub struct Task {
+        pub title: String,
+        pub done: bool,
+    }
+
     #[hdk_entry_types]
     #[unit_enum(UnitEntryTypes)]
     #[derive(Clone)]
     pub enum EntryTypes {
         #[entry_type(required_validations = 5)]
         Example(Example),
+        Note(Note),
+        Task(Task),
     }
 
     #[hdk_link_types]
@@ -229,19 +984,26 @@ pub mod example {
     #[serde(untagged)]
     pub enum SignalTypes {
         Example(ActionSignal<Example>),
+        Task(ActionSignal<Task>),
     }
     impl From<ActionSignal<Example>> for SignalTypes {
         fn from(value: ActionSignal<Example>) -> Self {
             SignalTypes::Example(value)
         }
     }
+    impl From<ActionSignal<Task>> for SignalTypes {
+        fn from(value: ActionSignal<Task>) -> Self {
+            SignalTypes::Task(value)
+        }
+    }
 
     /// NOT GENERATED
     /// Signal Receiver
     /// (forwards signals to the UI)
     /// would be handling a
+    /// The same function can be generated with `crud_signals!(SignalTypes)`
     pub fn recv_remote_signal(signal: ExternIO) -> ExternResult<()> {
-        Ok(emit_signal(&signal)?)
+        receive::<SignalTypes, _>(signal, any_sender)
     }
 
     /// NOT GENERATED
@@ -265,3 +1027,45 @@ pub mod example {
         SignalTypes
     );
 }
+
+/// Take a look at this module to see the keyword form of the crud! macro,
+/// once with only the required options, and once with every option given.
+/// It shares the entry, link and signal types of [example].
+/// With only the required options, [Note](example::Note) gets all of the zome functions of
+/// [example], named after the type, such as [create_note](keyword_example::create_note)
+/// and [fetch_notes](keyword_example::fetch_notes), with entries linked off of the `"note"` Path,
+/// and no signals.
+/// [Task](example::Task) gets zome functions named after `todo`, such as
+/// [create_todo](keyword_example::create_todo), but only for creating, fetching and updating,
+/// along with [fetch_todos_in_time_range](keyword_example::fetch_todos_in_time_range)
+/// and the other fetch functions of a time index bucketed by the minute.
+#[cfg(all(not(feature = "no_example"), not(feature = "mock")))]
+pub mod keyword_example {
+    use super::example::*;
+    use crate::modify_chain::ownership::Ownership;
+    use crate::retrieval::conflict_resolution::ConflictStrategy;
+    use hdk::prelude::*;
+
+    crud!(Note {
+        entry_types: EntryTypes,
+        link_types: LinkTypes,
+        link_type: LinkTypes::All,
+    });
+
+    crud!(Task {
+        entry_types: EntryTypes,
+        entry_type: EntryTypes::Task,
+        link_types: LinkTypes,
+        link_type: LinkTypes::All,
+        name: todo,
+        path: "todos",
+        signals: SignalTypes via get_peers,
+        conflict_strategy: ConflictStrategy::FirstWriterWins,
+        ownership: Ownership::AuthorOnly,
+        get_options: GetOptions::local(),
+        ops: [create, fetch, update],
+        time_index: Minute,
+        signal_payload: Diff,
+        local_signals: true,
+    });
+}
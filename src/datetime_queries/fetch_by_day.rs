@@ -1,5 +1,10 @@
 use crate::datetime_queries::inputs::FetchEntriesTime;
-use crate::datetime_queries::utils::{day_path_from_date, err, get_last_component_string};
+use crate::datetime_queries::utils::{
+    day_path_from_date, get_last_component_string, is_time_path_link,
+};
+use crate::errors::CrudError;
+use crate::retrieval::outputs::FetchReport;
+use crate::retrieval::utils::{dedup_by_action_hash, get_latest_for_targets, get_options_for};
 use crate::wire_record::WireRecord;
 use hdk::prelude::*;
 
@@ -31,8 +36,43 @@ impl FetchByDay {
         link_type_filter: LinkTypeFilter,
         link_type: TY,
         time: FetchEntriesTime,
+        get_strategy: GetStrategy,
         base_component: String,
     ) -> Result<Vec<WireRecord<EntryType>>, WasmError>
+    where
+        ScopedLinkType: TryFrom<TY, Error = E>,
+        TY: Clone,
+        WasmError: From<E>,
+    {
+        Ok(self
+            .fetch_entries_by_day_report::<EntryType, TY, E>(
+                fetch_by_hour,
+                get_latest_entry,
+                link_type_filter,
+                link_type,
+                time,
+                get_strategy,
+                base_component,
+            )?
+            .records)
+    }
+
+    /// like [fetch_entries_by_day](FetchByDay::fetch_entries_by_day) but also reports which of the
+    /// entries linked to the time paths of the day could not be fetched, and why
+    pub fn fetch_entries_by_day_report<
+        EntryType: 'static + TryFrom<SerializedBytes, Error = SerializedBytesError>,
+        TY,
+        E,
+    >(
+        &self,
+        fetch_by_hour: &FetchByHour,
+        get_latest_entry: &GetLatestEntry,
+        link_type_filter: LinkTypeFilter,
+        link_type: TY,
+        time: FetchEntriesTime,
+        get_strategy: GetStrategy,
+        base_component: String,
+    ) -> Result<FetchReport<EntryType>, WasmError>
     where
         ScopedLinkType: TryFrom<TY, Error = E>,
         TY: Clone,
@@ -46,179 +86,135 @@ impl FetchByDay {
             time.day,
         )?;
         // TODO: wrap in path.exists which would add extra hdk calls to be mocked in the test
-        let children = path.children()?;
-        let entries = children
+        let input =
+            GetLinksInputBuilder::try_new(path.path_entry_hash()?, link_type_filter.clone())?
+                .get_options(get_strategy);
+        let (mut hour_links, entry_links): (Vec<Link>, Vec<Link>) = get_links(input.build())?
             .into_iter()
-            .map(|hour_link| {
-                let hour_str = get_last_component_string(hour_link.tag)?;
-                let hour = hour_str.parse::<u32>().or(Err(err("Invalid path")))?;
-                fetch_by_hour.fetch_entries_by_hour::<EntryType, TY, E>(
-                    &get_latest_entry,
+            .partition(is_time_path_link);
+        // only need one link to each hour path
+        hour_links.sort_unstable_by(|a, b| a.tag.cmp(&b.tag));
+        hour_links.dedup_by(|a, b| a.tag.eq(&b.tag));
+        let mut report = FetchReport::default();
+        for hour_link in hour_links {
+            let hour_str = get_last_component_string(hour_link.tag)?;
+            let hour = hour_str
+                .parse::<u32>()
+                .map_err(|_| CrudError::InvalidPath(hour_str.clone()))?;
+            report.extend(
+                fetch_by_hour.fetch_entries_by_hour_report::<EntryType, TY, E>(
+                    get_latest_entry,
                     link_type_filter.clone(),
                     link_type.clone(),
                     time.year,
                     time.month,
                     time.day,
                     hour,
+                    get_strategy,
                     base_component.clone(),
-                )
-            })
-            .filter_map(Result::ok)
-            .flatten()
-            .collect();
-        Ok(entries)
+                )?,
+            );
+        }
+        // entries of a time index with the granularity of a day are linked straight off of the day path
+        let targets = entry_links.into_iter().map(|link| link.target).collect();
+        report.extend(get_latest_for_targets::<EntryType>(
+            get_latest_entry,
+            targets,
+            get_options_for(get_strategy),
+        ));
+        report.records = dedup_by_action_hash(report.records);
+        Ok(report)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::crud::example::Example;
-    use crate::datetime_queries::fetch_by_hour;
-    use crate::datetime_queries::inputs::FetchEntriesTime;
-    use crate::retrieval::get_latest_for_entry;
-    use crate::wire_record::WireRecord;
-    use ::fixt::prelude::*;
-    use hdk::hash_path::path::{Component, DHT_PREFIX};
+    use crate::datetime_queries::fetch_by_hour::MockFetchByHour;
+    use crate::datetime_queries::utils::{day_path_from_date, hour_path_from_date};
+    use crate::retrieval::get_latest_for_entry::MockGetLatestEntry;
+    use crate::retrieval::outputs::{FetchFailure, FetchFailureReason, FetchReport};
+    use crate::test_helpers::*;
     use hdk::prelude::*;
-    use holochain_types::prelude::RecordFixturator;
-
-    #[test]
-    fn test_fetch_entries_by_day() {
-        let mut mock_hdk = MockHdkT::new();
-
-        // when fetch_entries_by_day calls path.children(), assuming the path already exists, the following hdk
-        // functions are called: hash_entry x4, get, get_links
-
-        // set up for the first expected hash_entry call
 
-        let path = Path::from("create.2021-10-15");
-        let path_hash = fixt!(EntryHash);
-        let path_entry = PathEntry::new(path_hash.clone());
-        let path_entry_hash = fixt!(EntryHash);
-        mock_hdk
-            .expect_hash_entry()
-            .with(mockall::predicate::eq(
-                Entry::try_from(path.clone()).unwrap(),
-            ))
-            .times(1)
-            .return_const(Ok(path_hash.clone()));
-
-        mock_hdk
-            .expect_hash_entry()
-            .with(mockall::predicate::eq(
-                Entry::try_from(path_entry.clone()).unwrap(),
-            ))
-            .times(1)
-            .return_const(Ok(path_entry_hash.clone()));
-        mock_hdk
-            .expect_hash_entry()
-            .with(mockall::predicate::eq(
-                Entry::try_from(path.clone()).unwrap(),
-            ))
-            .times(1)
-            .return_const(Ok(path_hash.clone()));
-
-        mock_hdk
-            .expect_hash_entry()
-            .with(mockall::predicate::eq(
-                Entry::try_from(path_entry.clone()).unwrap(),
-            ))
-            .times(1)
-            .return_const(Ok(path_entry_hash.clone()));
-
-        // // set up for expected get call
-        let path_get_input = vec![GetInput::new(
-            AnyDhtHash::from(path_entry_hash.clone()),
-            GetOptions::local(),
-        )];
-        let expected_get_output = vec![Some(fixt!(Record))]; // this should return the path
-        mock_hdk
-            .expect_get()
-            .with(mockall::predicate::eq(path_get_input))
-            .times(1)
-            .return_const(Ok(expected_get_output));
-
-        // set up input for get links, the second parameter is the default used by the Holochain code
-        let get_links_input = vec![GetLinksInput::new(
-            path_entry_hash,
-            Some(holochain_zome_types::link::LinkTag::new([DHT_PREFIX])),
-        )];
-
-        // creating an expected output of get_links, which is a Vec<Links>, and Links is a Vec<Link>
-        // since the link tag is used to get the hour component from the path, it must be constructed properly
-        let hour_component = Component::from(String::from("10"));
-        let link_tag = LinkTag::new(
-            [DHT_PREFIX]
-                .iter()
-                .chain(
-                    <Vec<u8>>::from(UnsafeBytes::from(
-                        SerializedBytes::try_from(hour_component).unwrap(),
-                    ))
-                    .iter(),
-                )
-                .cloned()
-                .collect::<Vec<u8>>(),
-        );
-
-        let link_output = Link {
-            target: fixt![EntryHash],
-            timestamp: fixt![Timestamp],
-            tag: link_tag,
-            create_link_hash: fixt![ActionHash],
-        };
-
-        // here we are assuming there is only one hour component to the day path, however if we wanted to
-        // make sure the code properly cycles through each hour component, we would add extra Link records
-        // to the below vector
-        let get_links_output = vec![vec![link_output]];
+    fn failure(n: u8, reason: FetchFailureReason) -> FetchFailure {
+        FetchFailure {
+            hash: AnyLinkableHash::from(entry_hash(n)).into(),
+            reason,
+        }
+    }
 
-        mock_hdk
-            .expect_get_links()
-            .with(mockall::predicate::eq(get_links_input))
-            .times(1)
-            .return_const(Ok(get_links_output));
+    #[test]
+    fn test_fetch_entries_by_day_report() {
+        let store = set_link_store();
 
-        // initializing expected inputs and outputs for the mocked fetch_entries_by_hour
-        let fetch_time = FetchEntriesTime {
-            year: 2021,
-            month: 10 as u32,
-            day: 15 as u32,
-            hour: None,
-        };
+        // entries indexed by the hour, and entries of a day granularity index linked straight off of the day
         let base_component = "create".to_string();
-        let hour_entry = WireRecord::<Example> {
-            action_hash: fixt![ActionHashB64],
-            entry_hash: fixt![EntryHashB64],
-            entry: Example { number: 1 },
-            created_at: fixt![Timestamp],
-            updated_at: fixt![Timestamp],
-        };
-        let hour_entries: Vec<WireRecord<Example>> = vec![hour_entry];
-        // set up a mock of fetch_entries_by_hour
-        let mut mock_queries = fetch_by_hour::MockFetchByHour::new();
-        let mock_latest_entry = get_latest_for_entry::MockGetLatestEntry::new();
-        mock_queries
-            .expect_fetch_entries_by_hour::<Example>()
+        hour_path_from_date(TestLinkType, base_component.clone(), 2021, 10, 15, 10)
+            .unwrap()
+            .ensure()
+            .unwrap();
+        let day_path =
+            day_path_from_date(TestLinkType, base_component.clone(), 2021, 10, 15).unwrap();
+        for n in [4, 5, 6] {
+            store.add(link(
+                day_path.path_entry_hash().unwrap().into(),
+                entry_hash(n).into(),
+                0,
+                LinkTag::new(vec![]),
+            ));
+        }
+
+        let mut mock_fetch_by_hour = MockFetchByHour::new();
+        mock_fetch_by_hour
+            .expect_fetch_entries_by_hour_report::<Example, TestLinkType, WasmError>()
             .with(
-                // MockGetLatestEntry does not implement PartialEq so can't be compared
                 mockall::predicate::always(),
-                mockall::predicate::eq(fetch_time.year),
-                mockall::predicate::eq(fetch_time.month),
-                mockall::predicate::eq(fetch_time.day),
-                mockall::predicate::eq(10 as u32),
+                mockall::predicate::always(),
+                mockall::predicate::always(),
+                mockall::predicate::eq(2021),
+                mockall::predicate::eq(10),
+                mockall::predicate::eq(15),
+                mockall::predicate::eq(10),
+                mockall::predicate::always(),
                 mockall::predicate::eq(base_component.clone()),
             )
             .times(1)
-            .return_const(Ok(hour_entries.clone()));
-
-        set_hdk(mock_hdk);
-        let fetch_by_day = super::FetchByDay {};
-        let result = fetch_by_day.fetch_entries_by_day::<Example>(
-            &mock_queries,
-            &mock_latest_entry,
-            fetch_time,
+            .returning(|_, _, _, _, _, _, _, _, _| {
+                Ok(FetchReport {
+                    records: vec![wire_record(1, 0, 0)],
+                    failures: vec![failure(2, FetchFailureReason::NotFound)],
+                })
+            });
+        let mut mock_get_latest = MockGetLatestEntry::new();
+        mock_get_latest
+            .expect_try_get_latest_for_entry::<Example>()
+            .times(3)
+            .returning(|entry_hash, _| match entry_hash.get_raw_36()[0] {
+                4 => Ok(Err(FetchFailureReason::Deleted)),
+                5 => Ok(Err(FetchFailureReason::WrongType)),
+                n => Ok(Ok(wire_record(n, 0, 0))),
+            });
+
+        let result = super::FetchByDay {}.fetch_entries_by_day_report::<Example, _, _>(
+            &mock_fetch_by_hour,
+            &mock_get_latest,
+            link_type_filter(),
+            TestLinkType,
+            fetch_time(2021, 10, 15, None),
+            GetStrategy::Local,
             base_component,
         );
-        assert_eq!(result, Ok(hour_entries));
+        assert_eq!(
+            result,
+            Ok(FetchReport {
+                records: vec![wire_record(1, 0, 0), wire_record(6, 0, 0)],
+                failures: vec![
+                    failure(2, FetchFailureReason::NotFound),
+                    failure(4, FetchFailureReason::Deleted),
+                    failure(5, FetchFailureReason::WrongType),
+                ],
+            })
+        );
     }
 }
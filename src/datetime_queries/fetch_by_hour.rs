@@ -1,10 +1,14 @@
-use crate::datetime_queries::utils::hour_path_from_date;
+use crate::datetime_queries::inputs::FetchEntriesTime;
+use crate::datetime_queries::utils::{
+    get_time_path_targets, hour_path_from_date, time_path_from_time,
+};
+use crate::retrieval::outputs::FetchReport;
+use crate::retrieval::utils::{get_latest_for_targets, get_options_for};
 use crate::wire_record::WireRecord;
 use hdk::prelude::*;
 
 #[cfg(feature = "mock")]
 use ::mockall::automock;
-use std::convert::identity;
 
 #[cfg(not(feature = "mock"))]
 use crate::retrieval::get_latest_for_entry::GetLatestEntry;
@@ -27,120 +31,192 @@ impl FetchByHour {
         month: u32,
         day: u32,
         hour: u32,
+        get_strategy: GetStrategy,
         base_component: String,
     ) -> Result<Vec<WireRecord<EntryType>>, WasmError>
+    where
+        ScopedLinkType: TryFrom<TY, Error = E>,
+        TY: Clone,
+        WasmError: From<E>,
+    {
+        Ok(self
+            .fetch_entries_by_hour_report::<EntryType, TY, E>(
+                get_latest_entry,
+                link_type_filter,
+                link_type,
+                year,
+                month,
+                day,
+                hour,
+                get_strategy,
+                base_component,
+            )?
+            .records)
+    }
+
+    /// like [fetch_entries_by_hour](FetchByHour::fetch_entries_by_hour) but also reports which of the
+    /// entries linked to the time path could not be fetched, and why
+    pub fn fetch_entries_by_hour_report<
+        EntryType: 'static + TryFrom<SerializedBytes, Error = SerializedBytesError>,
+        TY,
+        E,
+    >(
+        &self,
+        get_latest_entry: &GetLatestEntry,
+        link_type_filter: LinkTypeFilter,
+        link_type: TY,
+        year: i32,
+        month: u32,
+        day: u32,
+        hour: u32,
+        get_strategy: GetStrategy,
+        base_component: String,
+    ) -> Result<FetchReport<EntryType>, WasmError>
     where
         ScopedLinkType: TryFrom<TY, Error = E>,
         TY: Clone,
         WasmError: From<E>,
     {
         let path = hour_path_from_date(link_type, base_component.clone(), year, month, day, hour)?;
-        let input = GetLinksInputBuilder::try_new(path.path_entry_hash()?, link_type_filter)?;
-        let links = get_links(input.build())?;
+        // the entries can be linked off of minute or second paths below the hour
+        let targets =
+            get_time_path_targets(path.path_entry_hash()?, link_type_filter, get_strategy)?;
+        Ok(get_latest_for_targets::<EntryType>(
+            get_latest_entry,
+            targets,
+            get_options_for(get_strategy),
+        ))
+    }
 
-        let entries: Vec<WireRecord<EntryType>> = links
-            .into_iter()
-            .map(|link| {
-                get_latest_entry.get_latest_for_entry::<EntryType>(
-                    link.target.try_into().map_err(|_| {
-                        wasm_error!(WasmErrorInner::Guest("Target is not an entry".to_string()))
-                    })?,
-                    GetOptions::network(),
-                )
-            })
-            .filter_map(Result::ok)
-            .filter_map(identity)
-            .map(|x| WireRecord::from(x))
-            .collect::<Vec<WireRecord<EntryType>>>();
-        Ok(entries)
+    /// fetches all entries linked to a time path index for the bucket that `time` selects,
+    /// which can be as narrow as a minute, or a second, of a time index which is that fine grained
+    pub fn fetch_entries_by_bucket<
+        EntryType: 'static + TryFrom<SerializedBytes, Error = SerializedBytesError>,
+        TY,
+        E,
+    >(
+        &self,
+        get_latest_entry: &GetLatestEntry,
+        link_type_filter: LinkTypeFilter,
+        link_type: TY,
+        time: FetchEntriesTime,
+        get_strategy: GetStrategy,
+        base_component: String,
+    ) -> Result<Vec<WireRecord<EntryType>>, WasmError>
+    where
+        ScopedLinkType: TryFrom<TY, Error = E>,
+        TY: Clone,
+        WasmError: From<E>,
+    {
+        let path = time_path_from_time(link_type, base_component, &time)?;
+        let targets =
+            get_time_path_targets(path.path_entry_hash()?, link_type_filter, get_strategy)?;
+        Ok(get_latest_for_targets::<EntryType>(
+            get_latest_entry,
+            targets,
+            get_options_for(get_strategy),
+        )
+        .records)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::crud::example::Example;
-    use crate::retrieval::get_latest_for_entry;
-    use crate::wire_record::WireRecord;
-    use ::fixt::prelude::*;
+    use crate::datetime_queries::inputs::FetchEntriesTime;
+    use crate::datetime_queries::utils::time_path_from_time;
+    use crate::retrieval::get_latest_for_entry::MockGetLatestEntry;
+    use crate::retrieval::outputs::{FetchFailure, FetchFailureReason, FetchReport};
+    use crate::test_helpers::*;
     use hdk::prelude::*;
+    use mockall::predicate::{always, eq};
 
-    #[test]
-    fn test_fetch_entries_by_hour() {
-        let mut mock_hdk = MockHdkT::new();
-
-        // set up for the first expected hash_entry call
-        let path = Path::from("create.2021-10-15.10");
-        let path_hash = fixt!(EntryHash);
-        let path_entry = PathEntry::new(path_hash.clone());
-        let path_entry_hash = fixt!(EntryHash);
-        mock_hdk
-            .expect_hash_entry()
-            .with(mockall::predicate::eq(
-                Entry::try_from(path.clone()).unwrap(),
-            ))
-            .times(1)
-            .return_const(Ok(path_hash.clone()));
-
-        mock_hdk
-            .expect_hash_entry()
-            .with(mockall::predicate::eq(
-                Entry::try_from(path_entry.clone()).unwrap(),
-            ))
-            .times(1)
-            .return_const(Ok(path_entry_hash.clone()));
-
-        let get_links_input = vec![GetLinksInput::new(path_entry_hash, None, None)];
-
-        // creating an expected output of get_links, which is a Vec<Links>, and Links is a Vec<Link>
-        let bytes: Vec<u8> = "10".try_into().unwrap();
-        let link_tag: LinkTag = LinkTag::new(bytes);
-
-        let link_output = Link {
-            target: fixt![EntryHash],
-            timestamp: fixt![Timestamp],
-            tag: link_tag,
-            create_link_hash: fixt![ActionHash],
+    /// entries 1 and 3 linked off of the 10th hour of the 15th of October, and entry 2
+    /// off of its 30th minute, of which all but entry 3 can be fetched
+    fn set_up_hour() -> MockGetLatestEntry {
+        let store = set_link_store();
+        let hour = fetch_time(2021, 10, 15, Some(10));
+        let minute = FetchEntriesTime {
+            minute: Some(30),
+            ..hour.clone()
         };
-
-        let get_links_output = vec![vec![link_output.clone()]];
-
-        mock_hdk
-            .expect_get_links()
-            .with(mockall::predicate::eq(get_links_input))
-            .times(1)
-            .return_const(Ok(get_links_output));
-
-        let get_latest_output = Some(WireRecord::<Example> {
-            action_hash: fixt![ActionHashB64],
-            entry_hash: fixt![EntryHashB64],
-            entry: Example { number: 1 },
-            created_at: fixt![Timestamp],
-            updated_at: fixt![Timestamp],
-        });
-
-        // set up a mock of get_latest_for_entry
-        let mut mock_get_latest = get_latest_for_entry::MockGetLatestEntry::new();
+        for (time, n) in [(&hour, 1), (&minute, 2), (&hour, 3)] {
+            let path = time_path_from_time(TestLinkType, "create".to_string(), time).unwrap();
+            path.ensure().unwrap();
+            store.add(link(
+                path.path_entry_hash().unwrap().into(),
+                entry_hash(n).into(),
+                0,
+                LinkTag::new("entry"),
+            ));
+        }
+        let mut mock_get_latest = MockGetLatestEntry::new();
+        mock_get_latest
+            .expect_try_get_latest_for_entry::<Example>()
+            .with(always(), eq(GetOptions::local()))
+            .returning(|hash, _| {
+                Ok(match hash == entry_hash(3) {
+                    true => Err(FetchFailureReason::NotFound),
+                    false => Ok(wire_record(hash.get_raw_36()[0], 0, 0)),
+                })
+            });
         mock_get_latest
-            .expect_get_latest_for_entry::<Example>()
-            .with(
-                mockall::predicate::eq(link_output.target),
-                mockall::predicate::eq(GetOptions::network()),
-            )
-            .times(1)
-            .return_const(Ok(get_latest_output.clone()));
+    }
 
-        set_hdk(mock_hdk);
-        let base_component = "create".to_string();
+    #[test]
+    fn test_fetch_entries_by_hour() {
+        let mock_get_latest = set_up_hour();
         let fetch_by_hour = super::FetchByHour {};
-        let result = fetch_by_hour.fetch_entries_by_hour::<Example>(
+        let result = fetch_by_hour.fetch_entries_by_hour_report::<Example, _, _>(
             &mock_get_latest,
+            link_type_filter(),
+            TestLinkType,
             2021,
-            10 as u32,
-            15 as u32,
-            10 as u32,
-            base_component,
+            10,
+            15,
+            10,
+            GetStrategy::Local,
+            "create".to_string(),
+        );
+        // the entries of the minute are found below the hour
+        assert_eq!(
+            result,
+            Ok(FetchReport {
+                records: vec![wire_record(1, 0, 0), wire_record(2, 0, 0)],
+                failures: vec![FetchFailure {
+                    hash: AnyLinkableHash::from(entry_hash(3)).into(),
+                    reason: FetchFailureReason::NotFound,
+                }],
+            })
+        );
+        let result = fetch_by_hour.fetch_entries_by_hour::<Example, _, _>(
+            &mock_get_latest,
+            link_type_filter(),
+            TestLinkType,
+            2021,
+            10,
+            15,
+            10,
+            GetStrategy::Local,
+            "create".to_string(),
+        );
+        assert_eq!(result, Ok(vec![wire_record(1, 0, 0), wire_record(2, 0, 0)]));
+    }
+
+    #[test]
+    fn test_fetch_entries_by_bucket() {
+        let mock_get_latest = set_up_hour();
+        let result = super::FetchByHour {}.fetch_entries_by_bucket::<Example, _, _>(
+            &mock_get_latest,
+            link_type_filter(),
+            TestLinkType,
+            FetchEntriesTime {
+                minute: Some(30),
+                ..fetch_time(2021, 10, 15, Some(10))
+            },
+            GetStrategy::Local,
+            "create".to_string(),
         );
-        let output = vec![WireRecord::from(get_latest_output.unwrap())];
-        assert_eq!(result, Ok(output));
+        assert_eq!(result, Ok(vec![wire_record(2, 0, 0)]));
     }
 }
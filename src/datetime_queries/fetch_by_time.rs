@@ -16,7 +16,8 @@ use crate::datetime_queries::fetch_by_hour::MockFetchByHour as FetchByHour;
 #[cfg(feature = "mock")]
 use crate::retrieval::get_latest_for_entry::MockGetLatestEntry as GetLatestEntry;
 
-/// fetches all entries linked to a time path index for either a specific day or hour of a day
+/// fetches all entries linked to a time path index for either a specific day or hour of a day,
+/// or a minute (or second) of an hour if the time index is that fine grained
 pub fn fetch_entries_by_time<
     EntryType: 'static + TryFrom<SerializedBytes, Error = SerializedBytesError>,
     TY,
@@ -28,6 +29,7 @@ pub fn fetch_entries_by_time<
     link_type_filter: LinkTypeFilter,
     link_type: TY,
     time: FetchEntriesTime,
+    get_strategy: GetStrategy,
     base_component: String,
 ) -> Result<Vec<WireRecord<EntryType>>, WasmError>
 where
@@ -42,122 +44,128 @@ where
             link_type_filter,
             link_type,
             time,
+            get_strategy,
             base_component,
         ),
-        Some(h) => fetch_by_hour.fetch_entries_by_hour(
-            &get_latest_entry,
-            link_type_filter,
-            link_type,
-            time.year,
-            time.month,
-            time.day,
-            h,
-            base_component,
-        ),
+        Some(h) => match time.minute {
+            None => fetch_by_hour.fetch_entries_by_hour(
+                &get_latest_entry,
+                link_type_filter,
+                link_type,
+                time.year,
+                time.month,
+                time.day,
+                h,
+                get_strategy,
+                base_component,
+            ),
+            Some(_) => fetch_by_hour.fetch_entries_by_bucket(
+                &get_latest_entry,
+                link_type_filter,
+                link_type,
+                time,
+                get_strategy,
+                base_component,
+            ),
+        },
     }?)
 }
 
 #[cfg(test)]
 mod tests {
     use crate::crud::example::Example;
+    use crate::datetime_queries::fetch_by_day::MockFetchByDay;
+    use crate::datetime_queries::fetch_by_hour::MockFetchByHour;
     use crate::datetime_queries::inputs::FetchEntriesTime;
-    use crate::datetime_queries::{fetch_by_day, fetch_by_hour};
-    use crate::retrieval::get_latest_for_entry;
-    use crate::wire_record::WireRecord;
-    use ::fixt::prelude::*;
+    use crate::retrieval::get_latest_for_entry::MockGetLatestEntry;
+    use crate::test_helpers::*;
     use hdk::prelude::*;
+    use mockall::predicate::{always, eq};
+
+    fn fetch_entries_by_time(
+        mock_fetch_by_day: &MockFetchByDay,
+        mock_fetch_by_hour: &MockFetchByHour,
+        time: FetchEntriesTime,
+    ) -> ExternResult<Vec<crate::wire_record::WireRecord<Example>>> {
+        super::fetch_entries_by_time::<Example, _, _>(
+            mock_fetch_by_day,
+            mock_fetch_by_hour,
+            &MockGetLatestEntry::new(),
+            link_type_filter(),
+            TestLinkType,
+            time,
+            GetStrategy::Local,
+            "create".to_string(),
+        )
+    }
 
     #[test]
     fn test_fetch_by_time_day() {
-        // when calling fetch_entries_by_time without
-        // an 'hour' then verify that it calls
-        // fetch_entries_by_day
-
-        let fetch_time = FetchEntriesTime {
-            year: 2021,
-            month: 10 as u32,
-            day: 15 as u32,
-            hour: None,
-        };
-
-        let base_component = "create".to_string();
-        let wire_record = WireRecord::<Example> {
-            action_hash: fixt![ActionHashB64],
-            entry_hash: fixt![EntryHashB64],
-            entry: Example { number: 1 },
-            created_at: fixt![Timestamp],
-            updated_at: fixt![Timestamp],
-        };
-        let wire_vec: Vec<WireRecord<Example>> = vec![wire_record];
-        let mut mock_fetch_by_day = fetch_by_day::MockFetchByDay::new();
-
+        // without an hour, the whole day is fetched
+        let time = fetch_time(2021, 10, 15, None);
+        let mut mock_fetch_by_day = MockFetchByDay::new();
         mock_fetch_by_day
-            .expect_fetch_entries_by_day::<Example>()
+            .expect_fetch_entries_by_day::<Example, TestLinkType, WasmError>()
             .with(
-                mockall::predicate::always(),
-                mockall::predicate::always(),
-                mockall::predicate::eq(fetch_time.clone()),
-                mockall::predicate::eq(base_component.clone()),
+                always(),
+                always(),
+                always(),
+                always(),
+                eq(time.clone()),
+                eq(GetStrategy::Local),
+                eq("create".to_string()),
             )
             .times(1)
-            .return_const(Ok(wire_vec.clone()));
-
-        let fetch_by_hour = fetch_by_hour::MockFetchByHour::new();
-        let get_latest_entry = get_latest_for_entry::MockGetLatestEntry::new();
-        let result = super::fetch_entries_by_time::<Example>(
-            &mock_fetch_by_day,
-            &fetch_by_hour,
-            &get_latest_entry,
-            fetch_time,
-            base_component,
-        );
-        assert_eq!(result, Ok(wire_vec));
+            .return_const(Ok(vec![wire_record(1, 0, 0)]));
+        let result = fetch_entries_by_time(&mock_fetch_by_day, &MockFetchByHour::new(), time);
+        assert_eq!(result, Ok(vec![wire_record(1, 0, 0)]));
     }
+
     #[test]
     fn test_fetch_by_time_hour() {
-        // when calling fetch_entries_by_time with
-        // an 'hour' then verify that it calls
-        // fetch_entries_by_hour
-
-        let fetch_time = FetchEntriesTime {
-            year: 2021,
-            month: 10 as u32,
-            day: 15 as u32,
-            hour: Some(10 as u32),
-        };
+        // with an hour, but no minute, the hour is fetched
+        let time = fetch_time(2021, 10, 15, Some(10));
+        let mut mock_fetch_by_hour = MockFetchByHour::new();
+        mock_fetch_by_hour
+            .expect_fetch_entries_by_hour::<Example, TestLinkType, WasmError>()
+            .with(
+                always(),
+                always(),
+                always(),
+                eq(2021),
+                eq(10),
+                eq(15),
+                eq(10),
+                eq(GetStrategy::Local),
+                eq("create".to_string()),
+            )
+            .times(1)
+            .return_const(Ok(vec![wire_record(1, 0, 0)]));
+        let result = fetch_entries_by_time(&MockFetchByDay::new(), &mock_fetch_by_hour, time);
+        assert_eq!(result, Ok(vec![wire_record(1, 0, 0)]));
+    }
 
-        let base_component = "create".to_string();
-        let wire_record = WireRecord::<Example> {
-            action_hash: fixt![ActionHashB64],
-            entry_hash: fixt![EntryHashB64],
-            entry: Example { number: 1 },
-            created_at: fixt![Timestamp],
-            updated_at: fixt![Timestamp],
+    #[test]
+    fn test_fetch_by_time_minute() {
+        // with a minute, just the bucket of that minute is fetched
+        let time = FetchEntriesTime {
+            minute: Some(30),
+            ..fetch_time(2021, 10, 15, Some(10))
         };
-        let wire_vec: Vec<WireRecord<Example>> = vec![wire_record];
-        let mock_fetch_by_day = fetch_by_day::MockFetchByDay::new();
-
-        let mut mock_fetch_by_hour = fetch_by_hour::MockFetchByHour::new();
+        let mut mock_fetch_by_hour = MockFetchByHour::new();
         mock_fetch_by_hour
-            .expect_fetch_entries_by_hour::<Example>()
+            .expect_fetch_entries_by_bucket::<Example, TestLinkType, WasmError>()
             .with(
-                mockall::predicate::always(),
-                mockall::predicate::eq(fetch_time.year),
-                mockall::predicate::eq(fetch_time.month),
-                mockall::predicate::eq(fetch_time.day),
-                mockall::predicate::eq(fetch_time.hour.unwrap()),
-                mockall::predicate::eq(base_component.clone()),
+                always(),
+                always(),
+                always(),
+                eq(time.clone()),
+                eq(GetStrategy::Local),
+                eq("create".to_string()),
             )
             .times(1)
-            .return_const(Ok(wire_vec.clone()));
-        let get_latest_entry = get_latest_for_entry::MockGetLatestEntry::new();
-        let result = super::fetch_entries_by_time::<Example>(
-            &mock_fetch_by_day,
-            &mock_fetch_by_hour,
-            &get_latest_entry,
-            fetch_time,
-            base_component,
-        );
-        assert_eq!(result, Ok(wire_vec));
+            .return_const(Ok(vec![wire_record(1, 0, 0)]));
+        let result = fetch_entries_by_time(&MockFetchByDay::new(), &mock_fetch_by_hour, time);
+        assert_eq!(result, Ok(vec![wire_record(1, 0, 0)]));
     }
 }
@@ -1,7 +1,8 @@
 use super::fetchers::Fetchers;
 use super::inputs::FetchEntriesTime;
+use super::utils::{existing_days, start_of_day};
+use crate::retrieval::utils::dedup_by_action_hash;
 use crate::wire_record::WireRecord;
-use chrono::Duration;
 use hdk::prelude::*;
 
 #[cfg(feature = "mock")]
@@ -22,6 +23,7 @@ impl FetchByDayDay {
         link_type: TY,
         start: FetchEntriesTime,
         end: FetchEntriesTime,
+        get_strategy: GetStrategy,
         base_component: String,
     ) -> Result<Vec<WireRecord<EntryType>>, WasmError>
     where
@@ -29,25 +31,26 @@ impl FetchByDayDay {
         TY: Clone,
         WasmError: From<E>,
     {
-        let mut dt = start.to_date_time();
         let mut entries = Vec::new();
-        let end = end.to_date_time();
-        while dt <= end {
-            entries.push(fetchers.day.fetch_entries_by_day::<EntryType, TY, E>(
+        // only visit the days which have been indexed
+        for day in existing_days(
+            link_type.clone(),
+            base_component.clone(),
+            start.to_date_time()?.date_naive(),
+            end.to_date_time()?.date_naive(),
+            get_strategy,
+        )? {
+            entries.extend(fetchers.day.fetch_entries_by_day::<EntryType, TY, E>(
                 &fetchers.hour,
                 &fetchers.get_latest,
                 link_type_filter.clone(),
                 link_type.clone(),
-                FetchEntriesTime::from_date_time(dt.clone()),
+                FetchEntriesTime::from_date_time(start_of_day(day)),
+                get_strategy,
                 base_component.clone(),
-            ));
-            dt = dt + Duration::days(1);
+            )?);
         }
-        Ok(entries
-            .into_iter()
-            .filter_map(Result::ok)
-            .flatten()
-            .collect())
+        Ok(dedup_by_action_hash(entries))
     }
 }
 
@@ -56,56 +59,70 @@ mod tests {
     use crate::crud::example::Example;
     use crate::datetime_queries::fetchers::Fetchers;
     use crate::datetime_queries::inputs::FetchEntriesTime;
-
-    use crate::wire_record::WireRecord;
-    use ::fixt::prelude::*;
+    use crate::test_helpers::*;
     use hdk::prelude::*;
+    use mockall::predicate::{always, eq, function};
+
     #[test]
     fn test_fetch_entries_from_day_to_day() {
-        let start_time = FetchEntriesTime {
-            year: 2021,
-            month: 10 as u32,
-            day: 20 as u32,
-            hour: None,
-        };
-        let end_time = FetchEntriesTime {
-            year: 2021,
-            month: 10 as u32,
-            day: 21 as u32,
-            hour: None,
-        };
-        let base_component = "create".to_string();
-        let wire_record = WireRecord::<Example> {
-            action_hash: fixt![ActionHashB64],
-            entry_hash: fixt![EntryHashB64],
-            entry: Example { number: 1 },
-            created_at: fixt![Timestamp],
-            updated_at: fixt![Timestamp],
-        };
-        let wire_vec: Vec<WireRecord<Example>> = vec![wire_record.clone()];
-        let wire_vec2 = vec![wire_record.clone(), wire_record.clone()];
-
+        set_link_store();
+        for day in [20, 21, 25] {
+            index_day("create", 2021, 10, day);
+        }
         let mut mock_fetchers = Fetchers::default();
-        // fetch_entries_by_day should be called for each day in the range
+        // only the days of the range which have been indexed are visited
         mock_fetchers
             .day
-            .expect_fetch_entries_by_day::<Example>()
+            .expect_fetch_entries_by_day::<Example, TestLinkType, WasmError>()
             .with(
-                mockall::predicate::always(),
-                mockall::predicate::always(),
-                mockall::predicate::always(),
-                mockall::predicate::eq(base_component.clone()),
+                always(),
+                always(),
+                always(),
+                always(),
+                function(|time: &FetchEntriesTime| time.day == 20 || time.day == 21),
+                always(),
+                eq("create".to_string()),
             )
             .times(2)
-            .return_const(Ok(wire_vec.clone()));
+            .return_const(Ok(vec![wire_record(1, 0, 0)]));
+        let result = super::FetchByDayDay {}.fetch_entries_from_day_to_day::<Example, _, _>(
+            &mock_fetchers,
+            link_type_filter(),
+            TestLinkType,
+            fetch_time(2021, 10, 20, None),
+            fetch_time(2021, 10, 22, None),
+            GetStrategy::Local,
+            "create".to_string(),
+        );
+        // the same record is returned for every bucket, but only once in the result
+        assert_eq!(result, Ok(vec![wire_record(1, 0, 0)]));
+    }
 
-        let fetch_day_day = super::FetchByDayDay {};
-        let result = fetch_day_day.fetch_entries_from_day_to_day::<Example>(
+    #[test]
+    fn test_fetch_entries_from_day_to_day_propagates_errors() {
+        set_link_store();
+        index_day("create", 2021, 10, 20);
+        let mut mock_fetchers = Fetchers::default();
+        mock_fetchers
+            .day
+            .expect_fetch_entries_by_day::<Example, TestLinkType, WasmError>()
+            .times(1)
+            .returning(|_, _, _, _, _, _, _| {
+                Err(wasm_error!(WasmErrorInner::Guest("unavailable".into())))
+            });
+        let result = super::FetchByDayDay {}.fetch_entries_from_day_to_day::<Example, _, _>(
             &mock_fetchers,
-            start_time,
-            end_time,
-            base_component.clone(),
+            link_type_filter(),
+            TestLinkType,
+            fetch_time(2021, 10, 20, None),
+            fetch_time(2021, 10, 21, None),
+            GetStrategy::Local,
+            "create".to_string(),
+        );
+        // a failing day is not mistaken for a day without any entries
+        assert_eq!(
+            result.map_err(|error| error.error),
+            Err(WasmErrorInner::Guest("unavailable".into()))
         );
-        assert_eq!(result, Ok(wire_vec2.clone()));
     }
 }
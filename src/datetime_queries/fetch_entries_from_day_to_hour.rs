@@ -1,5 +1,7 @@
 use super::fetchers::Fetchers;
 use crate::datetime_queries::inputs::FetchEntriesTime;
+use crate::datetime_queries::utils::{existing_days, start_of_day};
+use crate::retrieval::utils::dedup_by_action_hash;
 use crate::wire_record::WireRecord;
 use chrono::{Datelike, Duration, Timelike};
 use hdk::prelude::*;
@@ -22,6 +24,7 @@ impl FetchByDayHour {
         link_type: TY,
         start: FetchEntriesTime,
         end: FetchEntriesTime,
+        get_strategy: GetStrategy,
         base_component: String,
     ) -> Result<Vec<WireRecord<EntryType>>, WasmError>
     where
@@ -29,23 +32,30 @@ impl FetchByDayHour {
         TY: Clone,
         WasmError: From<E>,
     {
-        let mut dt = start.to_date_time();
         let mut entries = Vec::new();
-        let end = end.to_date_time();
-        let end_prev = end - Duration::days(1); // this is to prevent fetch entries by day being called on the last day (we don't want all the hours on the last day)
-        while dt < end_prev {
-            entries.push(fetchers.day.fetch_entries_by_day::<EntryType, TY, E>(
+        let end = end.to_date_time()?;
+        // all days but the last are fetched whole (we don't want all the hours on the last day),
+        // but only the days which have been indexed
+        for day in existing_days(
+            link_type.clone(),
+            base_component.clone(),
+            start.to_date_time()?.date_naive(),
+            end.date_naive() - Duration::days(1),
+            get_strategy,
+        )? {
+            entries.extend(fetchers.day.fetch_entries_by_day::<EntryType, TY, E>(
                 &fetchers.hour,
                 &fetchers.get_latest,
                 link_type_filter.clone(),
                 link_type.clone(),
-                FetchEntriesTime::from_date_time(dt.clone()),
+                FetchEntriesTime::from_date_time(start_of_day(day)),
+                get_strategy,
                 base_component.clone(),
-            ));
-            dt = dt + Duration::days(1);
+            )?);
         }
+        let mut dt = start_of_day(end.date_naive());
         while dt <= end {
-            entries.push(fetchers.hour.fetch_entries_by_hour::<EntryType, TY, E>(
+            entries.extend(fetchers.hour.fetch_entries_by_hour::<EntryType, TY, E>(
                 &fetchers.get_latest,
                 link_type_filter.clone(),
                 link_type.clone(),
@@ -53,15 +63,12 @@ impl FetchByDayHour {
                 dt.month(),
                 dt.day(),
                 dt.hour(),
+                get_strategy,
                 base_component.clone(),
-            ));
+            )?);
             dt = dt + Duration::hours(1);
         }
-        Ok(entries
-            .into_iter()
-            .filter_map(Result::ok)
-            .flatten()
-            .collect())
+        Ok(dedup_by_action_hash(entries))
     }
 }
 
@@ -70,77 +77,58 @@ mod tests {
     use crate::crud::example::Example;
     use crate::datetime_queries::fetchers::Fetchers;
     use crate::datetime_queries::inputs::FetchEntriesTime;
-
-    use crate::wire_record::WireRecord;
-    use ::fixt::prelude::*;
+    use crate::test_helpers::*;
     use hdk::prelude::*;
+    use mockall::predicate::{always, eq, function};
 
     #[test]
     fn test_fetch_entries_from_day_to_hour() {
-        // case 1: fetch by day is called once, and fetch by hour is called 3 times
-        // another case to try out would be same start and end day, so only fetch by hour is called
-        let start_time = FetchEntriesTime {
-            year: 2021,
-            month: 10 as u32,
-            day: 20 as u32,
-            hour: None,
-        };
-        let end_time = FetchEntriesTime {
-            year: 2021,
-            month: 10 as u32,
-            day: 21 as u32,
-            hour: Some(2 as u32),
-        };
-        let base_component = "create".to_string();
-        let wire_record = WireRecord::<Example> {
-            action_hash: fixt![ActionHashB64],
-            entry_hash: fixt![EntryHashB64],
-            entry: Example { number: 1 },
-            created_at: fixt![Timestamp],
-            updated_at: fixt![Timestamp],
-        };
-        let wire_vec: Vec<WireRecord<Example>> = vec![wire_record.clone()];
-        let wire_vec4 = vec![
-            wire_record.clone(),
-            wire_record.clone(),
-            wire_record.clone(),
-            wire_record.clone(),
-        ];
-
+        set_link_store();
+        for day in [20, 21, 22] {
+            index_day("create", 2021, 10, day);
+        }
         let mut mock_fetchers = Fetchers::default();
-        // fetch_entries_by_day should be called for each day in the range
+        // all days but the last are fetched whole, and the hours of the last day up to the end
         mock_fetchers
             .day
-            .expect_fetch_entries_by_day::<Example>()
+            .expect_fetch_entries_by_day::<Example, TestLinkType, WasmError>()
             .with(
-                mockall::predicate::always(),
-                mockall::predicate::always(),
-                mockall::predicate::always(),
-                mockall::predicate::eq(base_component.clone()),
+                always(),
+                always(),
+                always(),
+                always(),
+                function(|time: &FetchEntriesTime| time.day == 20 || time.day == 21),
+                always(),
+                eq("create".to_string()),
             )
-            .times(1)
-            .return_const(Ok(wire_vec.clone()));
-
+            .times(2)
+            .return_const(Ok(vec![wire_record(1, 0, 0)]));
         mock_fetchers
             .hour
-            .expect_fetch_entries_by_hour::<Example>()
+            .expect_fetch_entries_by_hour::<Example, TestLinkType, WasmError>()
             .with(
-                mockall::predicate::always(),
-                mockall::predicate::eq(start_time.year),
-                mockall::predicate::eq(start_time.month),
-                mockall::predicate::eq(end_time.day),
-                mockall::predicate::always(),
-                mockall::predicate::eq(base_component.clone()),
+                always(),
+                always(),
+                always(),
+                eq(2021),
+                eq(10),
+                eq(22),
+                always(),
+                always(),
+                eq("create".to_string()),
             )
-            .times(3)
-            .return_const(Ok(wire_vec.clone()));
-        let fetch_day_hour = super::FetchByDayHour {};
-        let result = fetch_day_hour.fetch_entries_from_day_to_hour::<Example>(
+            .times(2)
+            .return_const(Ok(vec![wire_record(1, 0, 0)]));
+        let result = super::FetchByDayHour {}.fetch_entries_from_day_to_hour::<Example, _, _>(
             &mock_fetchers,
-            start_time.clone(),
-            end_time.clone(),
-            base_component.clone(),
+            link_type_filter(),
+            TestLinkType,
+            fetch_time(2021, 10, 20, None),
+            fetch_time(2021, 10, 22, Some(1)),
+            GetStrategy::Local,
+            "create".to_string(),
         );
-        assert_eq!(result, Ok(wire_vec4.clone()));
+        // the same record is returned for every bucket, but only once in the result
+        assert_eq!(result, Ok(vec![wire_record(1, 0, 0)]));
     }
 }
@@ -1,6 +1,7 @@
 use super::fetchers::Fetchers;
 use super::inputs::FetchEntriesTime;
-use super::utils::next_day;
+use super::utils::{existing_days, next_day, start_of_day};
+use crate::retrieval::utils::dedup_by_action_hash;
 use crate::wire_record::WireRecord;
 use chrono::{Datelike, Duration, Timelike};
 use hdk::prelude::*;
@@ -23,6 +24,7 @@ impl FetchByHourDay {
         link_type: TY,
         start: FetchEntriesTime,
         end: FetchEntriesTime,
+        get_strategy: GetStrategy,
         base_component: String,
     ) -> Result<Vec<WireRecord<EntryType>>, WasmError>
     where
@@ -30,12 +32,12 @@ impl FetchByHourDay {
         TY: Clone,
         WasmError: From<E>,
     {
-        let mut dt = start.to_date_time();
+        let mut dt = start.to_date_time()?;
         let mut entries = Vec::new();
-        let end = end.to_date_time();
-        let second_day = next_day(dt.clone());
+        let end = end.to_date_time()?;
+        let second_day = next_day(dt.clone())?;
         while dt < second_day {
-            entries.push(fetchers.hour.fetch_entries_by_hour::<EntryType, TY, E>(
+            entries.extend(fetchers.hour.fetch_entries_by_hour::<EntryType, TY, E>(
                 &fetchers.get_latest,
                 link_type_filter.clone(),
                 link_type.clone(),
@@ -43,26 +45,30 @@ impl FetchByHourDay {
                 dt.month(),
                 dt.day(),
                 dt.hour(),
+                get_strategy,
                 base_component.clone(),
-            ));
+            )?);
             dt = dt + Duration::hours(1);
         }
-        while dt <= end {
-            entries.push(fetchers.day.fetch_entries_by_day::<EntryType, TY, E>(
+        // only visit the days which have been indexed
+        for day in existing_days(
+            link_type.clone(),
+            base_component.clone(),
+            second_day.date_naive(),
+            end.date_naive(),
+            get_strategy,
+        )? {
+            entries.extend(fetchers.day.fetch_entries_by_day::<EntryType, TY, E>(
                 &fetchers.hour,
                 &fetchers.get_latest,
                 link_type_filter.clone(),
                 link_type.clone(),
-                FetchEntriesTime::from_date_time(dt.clone()),
+                FetchEntriesTime::from_date_time(start_of_day(day)),
+                get_strategy,
                 base_component.clone(),
-            ));
-            dt = dt + Duration::days(1);
+            )?);
         }
-        Ok(entries
-            .into_iter()
-            .filter_map(Result::ok)
-            .flatten()
-            .collect())
+        Ok(dedup_by_action_hash(entries))
     }
 }
 
@@ -71,75 +77,58 @@ mod tests {
     use crate::crud::example::Example;
     use crate::datetime_queries::fetchers::Fetchers;
     use crate::datetime_queries::inputs::FetchEntriesTime;
-
-    use crate::wire_record::WireRecord;
-    use ::fixt::prelude::*;
+    use crate::test_helpers::*;
     use hdk::prelude::*;
-    #[test]
-    fn test_fetch_entries_from_day_to_hour() {
-        // should be called for 22, 23, then twice for next two days
-        let start_time = FetchEntriesTime {
-            year: 2021,
-            month: 10 as u32,
-            day: 20 as u32,
-            hour: Some(22 as u32),
-        };
-        let end_time = FetchEntriesTime {
-            year: 2021,
-            month: 10 as u32,
-            day: 22 as u32,
-            hour: None,
-        };
-        let base_component = "create".to_string();
-        let wire_record = WireRecord::<Example> {
-            action_hash: fixt![ActionHashB64],
-            entry_hash: fixt![EntryHashB64],
-            entry: Example { number: 1 },
-            created_at: fixt![Timestamp],
-            updated_at: fixt![Timestamp],
-        };
-        let wire_vec: Vec<WireRecord<Example>> = vec![wire_record.clone()];
-        let wire_vec4 = vec![
-            wire_record.clone(),
-            wire_record.clone(),
-            wire_record.clone(),
-            wire_record.clone(),
-        ];
+    use mockall::predicate::{always, eq, function};
 
+    #[test]
+    fn test_fetch_entries_from_hour_to_day() {
+        set_link_store();
+        for day in [20, 21, 22] {
+            index_day("create", 2021, 10, day);
+        }
         let mut mock_fetchers = Fetchers::default();
-        // could set up the expectation each time it is expected to be called to be able to check that the inputs are as expected
+        // the hours of the first day from the start, and the following days whole
         mock_fetchers
-            .day
-            .expect_fetch_entries_by_day::<Example>()
+            .hour
+            .expect_fetch_entries_by_hour::<Example, TestLinkType, WasmError>()
             .with(
-                mockall::predicate::always(),
-                mockall::predicate::always(),
-                mockall::predicate::always(),
-                mockall::predicate::eq(base_component.clone()),
+                always(),
+                always(),
+                always(),
+                eq(2021),
+                eq(10),
+                eq(20),
+                always(),
+                always(),
+                eq("create".to_string()),
             )
             .times(2)
-            .return_const(Ok(wire_vec.clone()));
-
+            .return_const(Ok(vec![wire_record(1, 0, 0)]));
         mock_fetchers
-            .hour
-            .expect_fetch_entries_by_hour::<Example>()
+            .day
+            .expect_fetch_entries_by_day::<Example, TestLinkType, WasmError>()
             .with(
-                mockall::predicate::always(),
-                mockall::predicate::eq(start_time.year),
-                mockall::predicate::eq(start_time.month),
-                mockall::predicate::eq(start_time.day),
-                mockall::predicate::always(),
-                mockall::predicate::eq(base_component.clone()),
+                always(),
+                always(),
+                always(),
+                always(),
+                function(|time: &FetchEntriesTime| time.day == 21 || time.day == 22),
+                always(),
+                eq("create".to_string()),
             )
             .times(2)
-            .return_const(Ok(wire_vec.clone()));
-        let fetch_hour_day = super::FetchByHourDay {};
-        let result = fetch_hour_day.fetch_entries_from_hour_to_day::<Example>(
+            .return_const(Ok(vec![wire_record(1, 0, 0)]));
+        let result = super::FetchByHourDay {}.fetch_entries_from_hour_to_day::<Example, _, _>(
             &mock_fetchers,
-            start_time.clone(),
-            end_time.clone(),
-            base_component.clone(),
+            link_type_filter(),
+            TestLinkType,
+            fetch_time(2021, 10, 20, Some(22)),
+            fetch_time(2021, 10, 22, None),
+            GetStrategy::Local,
+            "create".to_string(),
         );
-        assert_eq!(result, Ok(wire_vec4.clone()));
+        // the same record is returned for every bucket, but only once in the result
+        assert_eq!(result, Ok(vec![wire_record(1, 0, 0)]));
     }
 }
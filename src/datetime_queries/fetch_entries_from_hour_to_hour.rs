@@ -1,6 +1,7 @@
 use super::fetchers::Fetchers;
 use super::inputs::FetchEntriesTime;
-use super::utils::next_day;
+use super::utils::{existing_days, next_day, start_of_day};
+use crate::retrieval::utils::dedup_by_action_hash;
 use crate::wire_record::WireRecord;
 use chrono::{Datelike, Duration, Timelike};
 use hdk::prelude::*;
@@ -23,6 +24,7 @@ impl FetchByHourHour {
         link_type: TY,
         start: FetchEntriesTime,
         end: FetchEntriesTime,
+        get_strategy: GetStrategy,
         base_component: String,
     ) -> Result<Vec<WireRecord<EntryType>>, WasmError>
     where
@@ -30,18 +32,18 @@ impl FetchByHourHour {
         TY: Clone,
         WasmError: From<E>,
     {
-        let mut dt = start.to_date_time();
+        let mut dt = start.to_date_time()?;
         let mut entries = Vec::new();
-        let end = end.to_date_time();
-        let second_day = next_day(dt.clone());
+        let end = end.to_date_time()?;
+        let second_day = next_day(dt.clone())?;
         let second_last_day = end.clone() - Duration::days(1);
 
         // if hour range is on same day, skip first two loops
-        match next_day(dt.clone()) == next_day(end.clone()) {
+        match next_day(dt.clone())? == next_day(end.clone())? {
             true => {}
             false => {
                 while dt < second_day {
-                    entries.push(fetchers.hour.fetch_entries_by_hour::<EntryType, TY, E>(
+                    entries.extend(fetchers.hour.fetch_entries_by_hour::<EntryType, TY, E>(
                         &fetchers.get_latest,
                         link_type_filter.clone(),
                         link_type.clone(),
@@ -49,25 +51,34 @@ impl FetchByHourHour {
                         dt.month(),
                         dt.day(),
                         dt.hour(),
+                        get_strategy,
                         base_component.clone(),
-                    ));
+                    )?);
                     dt = dt + Duration::hours(1);
                 }
-                while dt <= second_last_day {
-                    entries.push(fetchers.day.fetch_entries_by_day::<EntryType, TY, E>(
+                // only visit the days in between which have been indexed
+                for day in existing_days(
+                    link_type.clone(),
+                    base_component.clone(),
+                    second_day.date_naive(),
+                    second_last_day.date_naive(),
+                    get_strategy,
+                )? {
+                    entries.extend(fetchers.day.fetch_entries_by_day::<EntryType, TY, E>(
                         &fetchers.hour,
                         &fetchers.get_latest,
                         link_type_filter.clone(),
                         link_type.clone(),
-                        FetchEntriesTime::from_date_time(dt.clone()),
+                        FetchEntriesTime::from_date_time(start_of_day(day)),
+                        get_strategy,
                         base_component.clone(),
-                    ));
-                    dt = dt + Duration::days(1);
+                    )?);
                 }
+                dt = start_of_day(end.date_naive());
             }
         }
         while dt <= end {
-            entries.push(fetchers.hour.fetch_entries_by_hour::<EntryType, TY, E>(
+            entries.extend(fetchers.hour.fetch_entries_by_hour::<EntryType, TY, E>(
                 &fetchers.get_latest,
                 link_type_filter.clone(),
                 link_type.clone(),
@@ -75,15 +86,12 @@ impl FetchByHourHour {
                 dt.month(),
                 dt.day(),
                 dt.hour(),
+                get_strategy,
                 base_component.clone(),
-            ));
+            )?);
             dt = dt + Duration::hours(1);
         }
-        Ok(entries
-            .into_iter()
-            .filter_map(Result::ok)
-            .flatten()
-            .collect())
+        Ok(dedup_by_action_hash(entries))
     }
 }
 
@@ -92,190 +100,125 @@ mod tests {
     use crate::crud::example::Example;
     use crate::datetime_queries::fetchers::Fetchers;
     use crate::datetime_queries::inputs::FetchEntriesTime;
-
-    use crate::wire_record::WireRecord;
-    use ::fixt::prelude::*;
+    use crate::test_helpers::*;
     use hdk::prelude::*;
-    #[test]
-    fn test_fetch_entries_from_day_to_hour() {
-        // cover at least three main cases: when start and end are on the same day
-        // when end is on the next day
-        // when there are multiple days between start and end (will call fetch by day)
-
-        // ============================================
-        // case 1: start and end on same day
-        // ============================================
-        // expect fetch by hour to be called 4 times
-        let start_time = FetchEntriesTime {
-            year: 2021,
-            month: 10 as u32,
-            day: 20 as u32,
-            hour: Some(2 as u32),
-        };
-        let end_time = FetchEntriesTime {
-            year: 2021,
-            month: 10 as u32,
-            day: 20 as u32,
-            hour: Some(5 as u32),
-        };
-        let base_component = "create".to_string();
-        let wire_record = WireRecord::<Example> {
-            action_hash: fixt![ActionHashB64],
-            entry_hash: fixt![EntryHashB64],
-            entry: Example { number: 1 },
-            created_at: fixt![Timestamp],
-            updated_at: fixt![Timestamp],
-        };
-        let wire_vec: Vec<WireRecord<Example>> = vec![wire_record.clone()];
-        let wire_vec4 = vec![
-            wire_record.clone(),
-            wire_record.clone(),
-            wire_record.clone(),
-            wire_record.clone(),
-        ];
+    use mockall::predicate::{always, eq, function};
 
+    #[test]
+    fn test_fetch_entries_from_hour_to_hour_on_the_same_day() {
+        set_link_store();
         let mut mock_fetchers = Fetchers::default();
         mock_fetchers
             .hour
-            .expect_fetch_entries_by_hour::<Example>()
+            .expect_fetch_entries_by_hour::<Example, TestLinkType, WasmError>()
             .with(
-                mockall::predicate::always(),
-                mockall::predicate::eq(start_time.year),
-                mockall::predicate::eq(start_time.month),
-                mockall::predicate::eq(start_time.day),
-                mockall::predicate::always(),
-                mockall::predicate::eq(base_component.clone()),
+                always(),
+                always(),
+                always(),
+                eq(2021),
+                eq(10),
+                eq(20),
+                always(),
+                always(),
+                eq("create".to_string()),
             )
             .times(4)
-            .return_const(Ok(wire_vec.clone()));
-        let fetch_hour_hour = super::FetchByHourHour {};
-        let result = fetch_hour_hour.fetch_entries_from_hour_to_hour::<Example>(
+            .return_const(Ok(vec![wire_record(1, 0, 0)]));
+        let result = super::FetchByHourHour {}.fetch_entries_from_hour_to_hour::<Example, _, _>(
             &mock_fetchers,
-            start_time.clone(),
-            end_time.clone(),
-            base_component.clone(),
+            link_type_filter(),
+            TestLinkType,
+            fetch_time(2021, 10, 20, Some(2)),
+            fetch_time(2021, 10, 20, Some(5)),
+            GetStrategy::Local,
+            "create".to_string(),
         );
-        assert_eq!(result, Ok(wire_vec4.clone()));
-        // ============================================
-        // case 2: end on next day
-        // ============================================
-        // expect fetch by hour to be called 4 times, fetch by day to be called 0 times
-        let start_time = FetchEntriesTime {
-            year: 2021,
-            month: 10 as u32,
-            day: 20 as u32,
-            hour: Some(22 as u32),
-        };
-        let end_time = FetchEntriesTime {
-            year: 2021,
-            month: 10 as u32,
-            day: 21 as u32,
-            hour: Some(1 as u32),
-        };
-        let base_component = "create".to_string();
-        let wire_record = WireRecord::<Example> {
-            action_hash: fixt![ActionHashB64],
-            entry_hash: fixt![EntryHashB64],
-            entry: Example { number: 1 },
-            created_at: fixt![Timestamp],
-            updated_at: fixt![Timestamp],
-        };
-        let wire_vec: Vec<WireRecord<Example>> = vec![wire_record.clone()];
-        let wire_vec4 = vec![
-            wire_record.clone(),
-            wire_record.clone(),
-            wire_record.clone(),
-            wire_record.clone(),
-        ];
+        // the same record is returned for every bucket, but only once in the result
+        assert_eq!(result, Ok(vec![wire_record(1, 0, 0)]));
+    }
 
+    #[test]
+    fn test_fetch_entries_from_hour_to_hour_on_the_next_day() {
+        set_link_store();
         let mut mock_fetchers = Fetchers::default();
+        // 22:00 and 23:00 on the first day, then 00:00 and 01:00 on the next
         mock_fetchers
             .hour
-            .expect_fetch_entries_by_hour::<Example>()
+            .expect_fetch_entries_by_hour::<Example, TestLinkType, WasmError>()
             .with(
-                mockall::predicate::always(),
-                mockall::predicate::eq(start_time.year),
-                mockall::predicate::eq(start_time.month),
-                mockall::predicate::always(),
-                mockall::predicate::always(),
-                mockall::predicate::eq(base_component.clone()),
+                always(),
+                always(),
+                always(),
+                eq(2021),
+                eq(10),
+                always(),
+                always(),
+                always(),
+                eq("create".to_string()),
             )
             .times(4)
-            .return_const(Ok(wire_vec.clone()));
-        let fetch_hour_hour = super::FetchByHourHour {};
-        let result = fetch_hour_hour.fetch_entries_from_hour_to_hour::<Example>(
+            .return_const(Ok(vec![wire_record(1, 0, 0)]));
+        let result = super::FetchByHourHour {}.fetch_entries_from_hour_to_hour::<Example, _, _>(
             &mock_fetchers,
-            start_time.clone(),
-            end_time.clone(),
-            base_component.clone(),
+            link_type_filter(),
+            TestLinkType,
+            fetch_time(2021, 10, 20, Some(22)),
+            fetch_time(2021, 10, 21, Some(1)),
+            GetStrategy::Local,
+            "create".to_string(),
         );
-        assert_eq!(result, Ok(wire_vec4.clone()));
-        // ============================================
-        // case 3: multiple days between start and end
-        // ============================================
-        // expect fetch by hour to be called 3 times, fetch by day to be called 4 times
-        let start_time = FetchEntriesTime {
-            year: 2021,
-            month: 10 as u32,
-            day: 20 as u32,
-            hour: Some(23 as u32),
-        };
-        let end_time = FetchEntriesTime {
-            year: 2021,
-            month: 10 as u32,
-            day: 22 as u32,
-            hour: Some(1 as u32),
-        };
-        let base_component = "create".to_string();
-        let wire_record = WireRecord::<Example> {
-            action_hash: fixt![ActionHashB64],
-            entry_hash: fixt![EntryHashB64],
-            entry: Example { number: 1 },
-            created_at: fixt![Timestamp],
-            updated_at: fixt![Timestamp],
-        };
-        let wire_vec: Vec<WireRecord<Example>> = vec![wire_record.clone()];
-        let wire_vec4 = vec![
-            wire_record.clone(),
-            wire_record.clone(),
-            wire_record.clone(),
-            wire_record.clone(),
-        ];
+        // the same record is returned for every bucket, but only once in the result
+        assert_eq!(result, Ok(vec![wire_record(1, 0, 0)]));
+    }
 
+    #[test]
+    fn test_fetch_entries_from_hour_to_hour_over_several_days() {
+        set_link_store();
+        for day in [20, 21, 22] {
+            index_day("create", 2021, 10, day);
+        }
         let mut mock_fetchers = Fetchers::default();
-        // could instead put in the expected FetchEntriesTime struct
+        // the days in between the first and the last are fetched whole
         mock_fetchers
             .day
-            .expect_fetch_entries_by_day::<Example>()
+            .expect_fetch_entries_by_day::<Example, TestLinkType, WasmError>()
             .with(
-                mockall::predicate::always(),
-                mockall::predicate::always(),
-                mockall::predicate::always(),
-                mockall::predicate::eq(base_component.clone()),
+                always(),
+                always(),
+                always(),
+                always(),
+                function(|time: &FetchEntriesTime| time.day == 21),
+                always(),
+                eq("create".to_string()),
             )
             .times(1)
-            .return_const(Ok(wire_vec.clone()));
-
+            .return_const(Ok(vec![wire_record(1, 0, 0)]));
         mock_fetchers
             .hour
-            .expect_fetch_entries_by_hour::<Example>()
+            .expect_fetch_entries_by_hour::<Example, TestLinkType, WasmError>()
             .with(
-                mockall::predicate::always(),
-                mockall::predicate::eq(start_time.year),
-                mockall::predicate::eq(start_time.month),
-                mockall::predicate::always(),
-                mockall::predicate::always(),
-                mockall::predicate::eq(base_component.clone()),
+                always(),
+                always(),
+                always(),
+                eq(2021),
+                eq(10),
+                function(|day: &u32| *day == 20 || *day == 22),
+                always(),
+                always(),
+                eq("create".to_string()),
             )
             .times(3)
-            .return_const(Ok(wire_vec.clone()));
-        let fetch_hour_hour = super::FetchByHourHour {};
-        let result = fetch_hour_hour.fetch_entries_from_hour_to_hour::<Example>(
+            .return_const(Ok(vec![wire_record(1, 0, 0)]));
+        let result = super::FetchByHourHour {}.fetch_entries_from_hour_to_hour::<Example, _, _>(
             &mock_fetchers,
-            start_time.clone(),
-            end_time.clone(),
-            base_component.clone(),
+            link_type_filter(),
+            TestLinkType,
+            fetch_time(2021, 10, 20, Some(23)),
+            fetch_time(2021, 10, 22, Some(1)),
+            GetStrategy::Local,
+            "create".to_string(),
         );
-        assert_eq!(result, Ok(wire_vec4.clone()));
+        // the same record is returned for every bucket, but only once in the result
+        assert_eq!(result, Ok(vec![wire_record(1, 0, 0)]));
     }
 }
@@ -1,9 +1,19 @@
 use hdk::prelude::*;
 
 use super::fetchers::Fetchers;
-use super::inputs::FetchEntriesTime;
-use super::utils::is_valid_date_range;
+use super::inputs::{
+    FetchEntriesTime, FetchEntriesTimeRange, FetchEntriesTimestampRange, SortOrder, TimeIndex,
+    TimeIndexGranularity, TimeIndexKind,
+};
+use super::utils::{
+    date_time_from_timestamp, existing_days, filter_within, is_valid_date_range,
+    local_window_to_utc, next_day, sort_by_time, start_of_day,
+};
+use crate::errors::CrudError;
+use crate::retrieval::utils::dedup_by_action_hash;
 use crate::wire_record::WireRecord;
+use chrono::{DateTime, Duration, Utc};
+use std::cmp::{max, min};
 
 /// fetches all entries of a certain type between two dates. Calls different sub methods depending on if an hour is suppled.
 pub fn fetch_entries_in_time_range<
@@ -16,6 +26,7 @@ pub fn fetch_entries_in_time_range<
     link_type: TY,
     start_time: FetchEntriesTime,
     end_time: FetchEntriesTime,
+    get_strategy: GetStrategy,
     base_component: String,
 ) -> Result<Vec<WireRecord<EntryType>>, WasmError>
 where
@@ -35,6 +46,7 @@ where
                         link_type,
                         start_time.clone(),
                         end_time.clone(),
+                        get_strategy,
                         base_component,
                     ),
                 Some(_) => {
@@ -47,6 +59,7 @@ where
                             link_type,
                             start_time.clone(),
                             end_time.clone(),
+                            get_strategy,
                             base_component,
                         )
                 }
@@ -64,6 +77,7 @@ where
                             link_type,
                             start_time.clone(),
                             end_time.clone(),
+                            get_strategy,
                             base_component,
                         )
                 }
@@ -77,6 +91,7 @@ where
                             link_type,
                             start_time.clone(),
                             end_time.clone(),
+                            get_strategy,
                             base_component,
                         )
                 }
@@ -85,173 +100,475 @@ where
     }
 }
 
+/// fetches all entries of a certain type which were created, or modified, depending on the `kind` of time index,
+/// within a window of local time, from the `start_time` of the `range` up to the end of its `end_time`,
+/// given at the `utc_offset` of the range. The `get_strategy` of the range defaults to the strategy of
+/// the `default_get_options`, such as the GetOptions given to `crud!`.
+/// The window is converted to UTC and fetched as with [fetch_entries_in_window]
+pub fn fetch_entries_in_local_time_range<
+    EntryType: 'static + TryFrom<SerializedBytes, Error = SerializedBytesError>,
+    TY,
+    E,
+>(
+    fetchers: &Fetchers,
+    link_type_filter: LinkTypeFilter,
+    link_type: TY,
+    range: FetchEntriesTimeRange,
+    kind: TimeIndexKind,
+    time_index: TimeIndex,
+    default_get_options: GetOptions,
+) -> Result<Vec<WireRecord<EntryType>>, WasmError>
+where
+    ScopedLinkType: TryFrom<TY, Error = E>,
+    TY: Clone,
+    WasmError: From<E>,
+{
+    let (start, end) = local_window_to_utc(&range.start_time, &range.end_time, range.utc_offset)?;
+    fetch_entries_in_window(
+        fetchers,
+        link_type_filter,
+        link_type,
+        start,
+        end,
+        kind,
+        range.order,
+        range.limit,
+        range.get_strategy.unwrap_or(default_get_options.strategy),
+        time_index,
+    )
+}
+
+/// fetches all entries of a certain type which were created, or modified, depending on the `kind` of time index,
+/// from the `start` timestamp of the `range` up to, but not including, its `end` timestamp.
+/// The `get_strategy` of the range defaults to the strategy of the `default_get_options`.
+pub fn fetch_entries_in_timestamp_range<
+    EntryType: 'static + TryFrom<SerializedBytes, Error = SerializedBytesError>,
+    TY,
+    E,
+>(
+    fetchers: &Fetchers,
+    link_type_filter: LinkTypeFilter,
+    link_type: TY,
+    range: FetchEntriesTimestampRange,
+    kind: TimeIndexKind,
+    time_index: TimeIndex,
+    default_get_options: GetOptions,
+) -> Result<Vec<WireRecord<EntryType>>, WasmError>
+where
+    ScopedLinkType: TryFrom<TY, Error = E>,
+    TY: Clone,
+    WasmError: From<E>,
+{
+    fetch_entries_in_window(
+        fetchers,
+        link_type_filter,
+        link_type,
+        date_time_from_timestamp(range.start)?,
+        date_time_from_timestamp(range.end)?,
+        kind,
+        range.order,
+        range.limit,
+        range.get_strategy.unwrap_or(default_get_options.strategy),
+        time_index,
+    )
+}
+
+/// fetches all entries of a certain type which were created, or modified, depending on the `kind` of time index,
+/// from `start` up to, but not including, `end`, sorted by that time in the given `order`, and at most `limit` of them.
+/// The days of the time index inside the window are fetched whole, and the hours of the days at its edges
+/// one at a time, unless the `time_index` only goes down to days. These are walked in `order`,
+/// until `limit` entries have been found. The entries found in each bucket are filtered down to the ones
+/// created, or last modified, precisely within both the bucket and the window, so that a record
+/// linked into several buckets of the modified index is only placed by its latest modification,
+/// and each record is only returned once, however many times it was linked into the time index.
+/// A `limit` of zero is rejected with [CrudError::InvalidPageLimit], just like the limit of a page.
+#[allow(clippy::too_many_arguments)]
+pub fn fetch_entries_in_window<
+    EntryType: 'static + TryFrom<SerializedBytes, Error = SerializedBytesError>,
+    TY,
+    E,
+>(
+    fetchers: &Fetchers,
+    link_type_filter: LinkTypeFilter,
+    link_type: TY,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    kind: TimeIndexKind,
+    order: SortOrder,
+    limit: Option<u32>,
+    get_strategy: GetStrategy,
+    time_index: TimeIndex,
+) -> Result<Vec<WireRecord<EntryType>>, WasmError>
+where
+    ScopedLinkType: TryFrom<TY, Error = E>,
+    TY: Clone,
+    WasmError: From<E>,
+{
+    if start >= end {
+        return Err(CrudError::InvalidDateRange.into());
+    }
+    if limit == Some(0) {
+        return Err(CrudError::InvalidPageLimit.into());
+    }
+    let limit = limit.map_or(usize::MAX, |limit| limit as usize);
+    let first_hour = FetchEntriesTime::from_date_time(start).to_date_time()?;
+    let last_moment = end
+        .checked_sub_signed(Duration::microseconds(1))
+        .ok_or_else(|| WasmError::from(CrudError::InvalidDateRange))?;
+    let mut buckets = Vec::new();
+    // only visit the days which have been indexed
+    for day in existing_days(
+        link_type.clone(),
+        time_index.base_component.clone(),
+        start.date_naive(),
+        last_moment.date_naive(),
+        get_strategy,
+    )? {
+        let day_start = start_of_day(day);
+        let day_end = next_day(day_start)?;
+        // a time index of days has no hours to narrow the days at the edges down to
+        match (day_start >= start && day_end <= end)
+            || time_index.granularity == TimeIndexGranularity::Day
+        {
+            true => buckets.push(FetchEntriesTime::from_date_time_with_granularity(
+                day_start,
+                TimeIndexGranularity::Day,
+            )),
+            false => {
+                let mut dt = max(day_start, first_hour);
+                while dt < min(day_end, end) {
+                    buckets.push(FetchEntriesTime::from_date_time(dt));
+                    dt = dt + Duration::hours(1);
+                }
+            }
+        }
+    }
+    if order == SortOrder::Desc {
+        buckets.reverse();
+    }
+    let mut entries = Vec::new();
+    for bucket in buckets {
+        if entries.len() >= limit {
+            break;
+        }
+        let bucket_start = max(bucket.to_precise_date_time()?, start);
+        let bucket_end = min(bucket.bucket_end()?, end);
+        let found = match bucket.hour {
+            None => fetchers.day.fetch_entries_by_day::<EntryType, TY, E>(
+                &fetchers.hour,
+                &fetchers.get_latest,
+                link_type_filter.clone(),
+                link_type.clone(),
+                bucket,
+                get_strategy,
+                time_index.base_component.clone(),
+            )?,
+            Some(hour) => fetchers.hour.fetch_entries_by_hour::<EntryType, TY, E>(
+                &fetchers.get_latest,
+                link_type_filter.clone(),
+                link_type.clone(),
+                bucket.year,
+                bucket.month,
+                bucket.day,
+                hour,
+                get_strategy,
+                time_index.base_component.clone(),
+            )?,
+        };
+        // the buckets don't overlap, so sorting each one sorts all of them
+        let mut found = filter_within(found, kind, bucket_start, bucket_end);
+        sort_by_time(&mut found, kind, order);
+        entries.extend(found);
+        // a record can be linked into the same bucket of the modified index more than once
+        entries = dedup_by_action_hash(entries);
+    }
+    entries.truncate(limit);
+    Ok(entries)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::crud::example::Example;
     use crate::datetime_queries::fetchers::Fetchers;
-    use crate::datetime_queries::inputs::FetchEntriesTime;
-
-    use crate::wire_record::WireRecord;
-    use ::fixt::prelude::*;
+    use crate::datetime_queries::inputs::{
+        FetchEntriesTime, FetchEntriesTimestampRange, SortOrder, TimeIndex, TimeIndexGranularity,
+        TimeIndexKind,
+    };
+    use crate::errors::CrudError;
+    use crate::test_helpers::*;
+    use chrono::{TimeZone, Utc};
     use hdk::prelude::*;
+    use mockall::predicate::{always, eq};
+
+    /// the microseconds since the epoch of an hour of October 2021
+    fn october(day: u32, hour: u32) -> i64 {
+        Utc.with_ymd_and_hms(2021, 10, day, hour, 0, 0)
+            .unwrap()
+            .timestamp_micros()
+    }
 
     #[test]
     fn test_fetch_in_time_range() {
-        // ==================================================
-        // day to day test
-        // ==================================================
-        // create inputs and outputs
-        let start_time = FetchEntriesTime {
-            year: 2021,
-            month: 10 as u32,
-            day: 20 as u32,
-            hour: None,
-        };
-        let end_time = FetchEntriesTime {
-            year: 2021,
-            month: 10 as u32,
-            day: 21 as u32,
-            hour: None,
-        };
         let base_component = "create".to_string();
-        let wire_record = WireRecord::<Example> {
-            action_hash: fixt![ActionHashB64],
-            entry_hash: fixt![EntryHashB64],
-            entry: Example { number: 1 },
-            created_at: fixt![Timestamp],
-            updated_at: fixt![Timestamp],
-        };
-        let wire_vec: Vec<WireRecord<Example>> = vec![wire_record];
+        let wire_vec = vec![wire_record(1, 0, 0)];
+        // each combination of days and hours is fetched by its own fetcher
+        for (start_hour, end_hour) in [
+            (None, None),
+            (None, Some(10)),
+            (Some(10), None),
+            (Some(10), Some(10)),
+        ] {
+            let start_time = fetch_time(2021, 10, 20, start_hour);
+            let end_time = fetch_time(2021, 10, 21, end_hour);
+            let mut mock_fetchers = Fetchers::default();
+            match (start_hour, end_hour) {
+                (None, None) => {
+                    mock_fetchers
+                        .day_to_day
+                        .expect_fetch_entries_from_day_to_day::<Example, TestLinkType, WasmError>()
+                        .with(
+                            always(),
+                            always(),
+                            always(),
+                            eq(start_time.clone()),
+                            eq(end_time.clone()),
+                            eq(GetStrategy::Local),
+                            eq(base_component.clone()),
+                        )
+                        .times(1)
+                        .return_const(Ok(wire_vec.clone()));
+                }
+                (None, Some(_)) => {
+                    mock_fetchers
+                        .day_to_hour
+                        .expect_fetch_entries_from_day_to_hour::<Example, TestLinkType, WasmError>()
+                        .with(
+                            always(),
+                            always(),
+                            always(),
+                            eq(start_time.clone()),
+                            eq(end_time.clone()),
+                            eq(GetStrategy::Local),
+                            eq(base_component.clone()),
+                        )
+                        .times(1)
+                        .return_const(Ok(wire_vec.clone()));
+                }
+                (Some(_), None) => {
+                    mock_fetchers
+                        .hour_to_day
+                        .expect_fetch_entries_from_hour_to_day::<Example, TestLinkType, WasmError>()
+                        .with(
+                            always(),
+                            always(),
+                            always(),
+                            eq(start_time.clone()),
+                            eq(end_time.clone()),
+                            eq(GetStrategy::Local),
+                            eq(base_component.clone()),
+                        )
+                        .times(1)
+                        .return_const(Ok(wire_vec.clone()));
+                }
+                (Some(_), Some(_)) => {
+                    mock_fetchers
+                        .hour_to_hour
+                        .expect_fetch_entries_from_hour_to_hour::<Example, TestLinkType, WasmError>(
+                        )
+                        .with(
+                            always(),
+                            always(),
+                            always(),
+                            eq(start_time.clone()),
+                            eq(end_time.clone()),
+                            eq(GetStrategy::Local),
+                            eq(base_component.clone()),
+                        )
+                        .times(1)
+                        .return_const(Ok(wire_vec.clone()));
+                }
+            }
+            let result = super::fetch_entries_in_time_range::<Example, _, _>(
+                &mock_fetchers,
+                link_type_filter(),
+                TestLinkType,
+                start_time,
+                end_time,
+                GetStrategy::Local,
+                base_component.clone(),
+            );
+            assert_eq!(result, Ok(wire_vec.clone()));
+        }
+    }
+
+    #[test]
+    fn test_fetch_entries_in_window_of_a_day_index() {
+        set_link_store();
+        index_day("todo_time", 2021, 10, 20);
+        index_day("todo_time", 2021, 10, 21);
         let mut mock_fetchers = Fetchers::default();
+        // the days at the edges of the window are fetched whole, as there are no hours to fetch
         mock_fetchers
-            .day_to_day
-            .expect_fetch_entries_from_day_to_day::<Example>()
-            .with(
-                mockall::predicate::always(),
-                mockall::predicate::eq(start_time.clone()),
-                mockall::predicate::eq(end_time.clone()),
-                mockall::predicate::eq(base_component.clone()),
-            )
-            .times(1)
-            .return_const(Ok(wire_vec.clone()));
-
-        let result = super::fetch_entries_in_time_range::<Example>(
+            .day
+            .expect_fetch_entries_by_day::<Example, TestLinkType, WasmError>()
+            .times(2)
+            .returning(|_, _, _, _, time: FetchEntriesTime, _, _| {
+                Ok(match time.day {
+                    20 => vec![
+                        wire_record(1, october(20, 10), october(20, 10)),
+                        wire_record(2, october(20, 13), october(20, 13)),
+                    ],
+                    _ => vec![
+                        wire_record(3, october(21, 5), october(21, 5)),
+                        wire_record(4, october(21, 7), october(21, 7)),
+                    ],
+                })
+            });
+        let result = super::fetch_entries_in_window::<Example, _, _>(
             &mock_fetchers,
-            start_time,
-            end_time,
-            base_component.clone(),
+            link_type_filter(),
+            TestLinkType,
+            Utc.with_ymd_and_hms(2021, 10, 20, 12, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2021, 10, 21, 6, 0, 0).unwrap(),
+            TimeIndexKind::Created,
+            SortOrder::Asc,
+            None,
+            GetStrategy::Local,
+            TimeIndex {
+                base_component: "todo_time".to_string(),
+                granularity: TimeIndexGranularity::Day,
+            },
         );
-        assert_eq!(result, Ok(wire_vec.clone()));
-        // ==================================================
-        // day to hour test
-        // ==================================================
+        // and then filtered down to the window
+        assert_eq!(
+            result,
+            Ok(vec![
+                wire_record(2, october(20, 13), october(20, 13)),
+                wire_record(3, october(21, 5), october(21, 5)),
+            ])
+        );
+    }
 
-        // create inputs and outputs
-        let start_time = FetchEntriesTime {
-            year: 2021,
-            month: 10 as u32,
-            day: 20 as u32,
-            hour: None,
-        };
-        let end_time = FetchEntriesTime {
-            year: 2021,
-            month: 10 as u32,
-            day: 21 as u32,
-            hour: Some(10 as u32),
-        };
+    #[test]
+    fn test_fetch_entries_in_window_propagates_errors() {
+        set_link_store();
+        index_day("todo_time", 2021, 10, 20);
         let mut mock_fetchers = Fetchers::default();
         mock_fetchers
-            .day_to_hour
-            .expect_fetch_entries_from_day_to_hour::<Example>()
-            .with(
-                mockall::predicate::always(),
-                mockall::predicate::eq(start_time.clone()),
-                mockall::predicate::eq(end_time.clone()),
-                mockall::predicate::eq(base_component.clone()),
-            )
+            .day
+            .expect_fetch_entries_by_day::<Example, TestLinkType, WasmError>()
             .times(1)
-            .return_const(Ok(wire_vec.clone()));
-
-        let result = super::fetch_entries_in_time_range::<Example>(
+            .returning(|_, _, _, _, _, _, _| {
+                Err(wasm_error!(WasmErrorInner::Guest("unavailable".into())))
+            });
+        let result = super::fetch_entries_in_window::<Example, _, _>(
             &mock_fetchers,
-            start_time,
-            end_time,
-            base_component.clone(),
+            link_type_filter(),
+            TestLinkType,
+            Utc.with_ymd_and_hms(2021, 10, 20, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2021, 10, 21, 0, 0, 0).unwrap(),
+            TimeIndexKind::Created,
+            SortOrder::Asc,
+            None,
+            GetStrategy::Local,
+            TimeIndex::from("todo_time".to_string()),
         );
-        assert_eq!(result, Ok(wire_vec.clone()));
-        // ==================================================
-        // hour to day test
-        // ==================================================
+        // a failing day is not mistaken for a day without any entries
+        assert_eq!(
+            result.map_err(|error| error.error),
+            Err(WasmErrorInner::Guest("unavailable".into()))
+        );
+    }
 
-        // create inputs and outputs
-        let start_time = FetchEntriesTime {
-            year: 2021,
-            month: 10 as u32,
-            day: 20 as u32,
-            hour: Some(10 as u32),
-        };
-        let end_time = FetchEntriesTime {
-            year: 2021,
-            month: 10 as u32,
-            day: 21 as u32,
-            hour: None,
-        };
+    #[test]
+    fn test_fetch_entries_modified_in_window() {
+        set_link_store();
+        index_day("todo_modified", 2021, 10, 20);
         let mut mock_fetchers = Fetchers::default();
+        // record 3 was created in the first hour, and updated in the third,
+        // so it is linked into both, but found as last modified in the third
         mock_fetchers
-            .hour_to_day
-            .expect_fetch_entries_from_hour_to_day::<Example>()
-            .with(
-                mockall::predicate::always(),
-                mockall::predicate::eq(start_time.clone()),
-                mockall::predicate::eq(end_time.clone()),
-                mockall::predicate::eq(base_component.clone()),
+            .hour
+            .expect_fetch_entries_by_hour::<Example, TestLinkType, WasmError>()
+            .returning(|_, _, _, _, _, _, hour: u32, _, _| {
+                Ok(match hour {
+                    0 => vec![
+                        wire_record(3, october(20, 0), october(20, 2)),
+                        wire_record(1, october(20, 0), october(20, 0)),
+                    ],
+                    1 => vec![wire_record(2, october(20, 1), october(20, 1))],
+                    _ => vec![wire_record(3, october(20, 0), october(20, 2))],
+                })
+            });
+        let fetch = |order, limit| {
+            super::fetch_entries_in_window::<Example, _, _>(
+                &mock_fetchers,
+                link_type_filter(),
+                TestLinkType,
+                Utc.with_ymd_and_hms(2021, 10, 20, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2021, 10, 20, 3, 0, 0).unwrap(),
+                TimeIndexKind::Modified,
+                order,
+                limit,
+                GetStrategy::Local,
+                TimeIndex::from("todo_modified".to_string()),
             )
-            .times(1)
-            .return_const(Ok(wire_vec.clone()));
+        };
+        assert_eq!(
+            fetch(SortOrder::Desc, None),
+            Ok(vec![
+                wire_record(3, october(20, 0), october(20, 2)),
+                wire_record(2, october(20, 1), october(20, 1)),
+                wire_record(1, october(20, 0), october(20, 0)),
+            ])
+        );
+        // the walk stops early, without placing record 3 by its first link
+        assert_eq!(
+            fetch(SortOrder::Asc, Some(2)),
+            Ok(vec![
+                wire_record(1, october(20, 0), october(20, 0)),
+                wire_record(2, october(20, 1), october(20, 1)),
+            ])
+        );
+    }
 
-        let result = super::fetch_entries_in_time_range::<Example>(
+    #[test]
+    fn test_fetch_entries_in_timestamp_range_out_of_range() {
+        let mock_fetchers = Fetchers::default();
+        // far beyond the last year chrono can represent, which is an error rather than a panic
+        let result = super::fetch_entries_in_timestamp_range::<Example, _, _>(
             &mock_fetchers,
-            start_time,
-            end_time,
-            base_component.clone(),
+            link_type_filter(),
+            TestLinkType,
+            FetchEntriesTimestampRange {
+                start: timestamp(october(20, 0)),
+                end: timestamp(i64::MAX),
+                order: SortOrder::Asc,
+                limit: None,
+                get_strategy: None,
+            },
+            TimeIndexKind::Created,
+            TimeIndex::from("todo_time".to_string()),
+            GetOptions::local(),
         );
-        assert_eq!(result, Ok(wire_vec.clone()));
-        // ==================================================
-        // hour to day test
-        // ==================================================
-
-        // create inputs and outputs
-        let start_time = FetchEntriesTime {
-            year: 2021,
-            month: 10 as u32,
-            day: 20 as u32,
-            hour: Some(10 as u32),
-        };
-        let end_time = FetchEntriesTime {
-            year: 2021,
-            month: 10 as u32,
-            day: 21 as u32,
-            hour: Some(10 as u32),
-        };
-        let mut mock_fetchers = Fetchers::default();
-        mock_fetchers
-            .hour_to_hour
-            .expect_fetch_entries_from_hour_to_hour::<Example>()
-            .with(
-                mockall::predicate::always(),
-                mockall::predicate::eq(start_time.clone()),
-                mockall::predicate::eq(end_time.clone()),
-                mockall::predicate::eq(base_component.clone()),
-            )
-            .times(1)
-            .return_const(Ok(wire_vec.clone()));
+        assert_eq!(result, Err(CrudError::InvalidDateRange.into()));
+    }
 
-        let result = super::fetch_entries_in_time_range::<Example>(
+    #[test]
+    fn test_fetch_entries_in_window_rejects_zero_limit() {
+        let mock_fetchers = Fetchers::default();
+        let result = super::fetch_entries_in_window::<Example, _, _>(
             &mock_fetchers,
-            start_time,
-            end_time,
-            base_component.clone(),
+            link_type_filter(),
+            TestLinkType,
+            Utc.with_ymd_and_hms(2021, 10, 20, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2021, 10, 21, 0, 0, 0).unwrap(),
+            TimeIndexKind::Created,
+            SortOrder::Asc,
+            Some(0),
+            GetStrategy::Local,
+            TimeIndex::from("todo_time".to_string()),
         );
-        assert_eq!(result, Ok(wire_vec.clone()));
+        assert_eq!(result, Err(CrudError::InvalidPageLimit.into()));
     }
 }
@@ -61,7 +61,7 @@ impl Fetchers {
             hour_to_hour: FetchByHourHour {},
             day: FetchByDay {},
             hour: FetchByHour {},
-            get_latest: GetLatestEntry {},
+            get_latest: GetLatestEntry::default(),
         }
     }
 }
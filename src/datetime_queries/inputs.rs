@@ -1,4 +1,6 @@
-use chrono::{DateTime, Datelike, NaiveDate, Timelike, Utc};
+use crate::errors::CrudError;
+use crate::wire_record::WireRecord;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Timelike, Utc};
 use hdk::prelude::*;
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct FetchEntriesTime {
@@ -6,27 +8,248 @@ pub struct FetchEntriesTime {
     pub month: u32,
     pub day: u32,
     pub hour: Option<u32>,
+    /// only used together with `hour`, to select a minute bucket of a finer grained time index
+    #[serde(default)]
+    pub minute: Option<u32>,
+    /// only used together with `minute`, to select a second bucket of a finer grained time index
+    #[serde(default)]
+    pub second: Option<u32>,
 }
 
 impl FetchEntriesTime {
-    pub fn to_date_time(&self) -> DateTime<Utc> {
-        match self.hour {
-            None => DateTime::from_utc(
-                NaiveDate::from_ymd(self.year, self.month, self.day).and_hms(0, 0, 0),
-                Utc,
-            ),
-            Some(h) => DateTime::from_utc(
-                NaiveDate::from_ymd(self.year, self.month, self.day).and_hms(h, 0, 0),
-                Utc,
-            ),
+    /// the start of the day, or hour of the day if `hour` is given.
+    /// Ranges are walked in whole hours, so `minute` and `second` are ignored
+    pub fn to_date_time(&self) -> ExternResult<DateTime<Utc>> {
+        Self {
+            minute: None,
+            second: None,
+            ..self.clone()
         }
+        .to_precise_date_time()
+    }
+    /// like [to_date_time](FetchEntriesTime::to_date_time) but down to the `minute` and `second`, if given.
+    /// Errors with [CrudError::InvalidDateRange] if this is not a valid time, such as the 31st of February
+    pub fn to_precise_date_time(&self) -> ExternResult<DateTime<Utc>> {
+        NaiveDate::from_ymd_opt(self.year, self.month, self.day)
+            .and_then(|date| {
+                date.and_hms_opt(
+                    self.hour.unwrap_or(0),
+                    self.minute.unwrap_or(0),
+                    self.second.unwrap_or(0),
+                )
+            })
+            .map(|date_time| date_time.and_utc())
+            .ok_or_else(|| CrudError::InvalidDateRange.into())
+    }
+    /// the first moment after the day, hour, minute or second which this time selects
+    pub fn bucket_end(&self) -> ExternResult<DateTime<Utc>> {
+        let length = match (self.hour, self.minute, self.second) {
+            (None, _, _) => Duration::days(1),
+            (Some(_), None, _) => Duration::hours(1),
+            (Some(_), Some(_), None) => Duration::minutes(1),
+            (Some(_), Some(_), Some(_)) => Duration::seconds(1),
+        };
+        self.to_precise_date_time()?
+            .checked_add_signed(length)
+            .ok_or_else(|| CrudError::InvalidDateRange.into())
     }
     pub fn from_date_time(dt: DateTime<Utc>) -> Self {
+        Self::from_date_time_with_granularity(dt, TimeIndexGranularity::Hour)
+    }
+    /// the time bucket of `granularity` which `dt` falls in
+    pub fn from_date_time_with_granularity(
+        dt: DateTime<Utc>,
+        granularity: TimeIndexGranularity,
+    ) -> Self {
         Self {
             year: dt.year(),
             month: dt.month(),
             day: dt.day(),
-            hour: Some(dt.hour()),
+            hour: match granularity >= TimeIndexGranularity::Hour {
+                true => Some(dt.hour()),
+                false => None,
+            },
+            minute: match granularity >= TimeIndexGranularity::Minute {
+                true => Some(dt.minute()),
+                false => None,
+            },
+            second: match granularity >= TimeIndexGranularity::Second {
+                true => Some(dt.second()),
+                false => None,
+            },
+        }
+    }
+}
+
+/// the input to a fetch of the entries in the bucket of the time index that `time` selects
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchEntriesTimeInput {
+    #[serde(flatten)]
+    pub time: FetchEntriesTime,
+    /// whether to fetch from the network, or only what this agent holds locally.
+    /// Defaults to the GetOptions given to `crud!`
+    #[serde(default)]
+    pub get_strategy: Option<GetStrategy>,
+}
+
+/// the input to a fetch of the entries created between `start_time` and `end_time`,
+/// including all of the day, hour, minute or second of `end_time`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchEntriesTimeRange {
+    pub start_time: FetchEntriesTime,
+    pub end_time: FetchEntriesTime,
+    /// the offset from UTC, in minutes, of the local time that `start_time` and `end_time` are given in,
+    /// such as `120` for UTC+2 or `-300` for UTC-5. Time indexes are always in UTC
+    #[serde(default)]
+    pub utc_offset: i32,
+    /// the order of the results, by when they were created. Defaults to oldest first
+    #[serde(default)]
+    pub order: SortOrder,
+    /// return at most this many results, the first ones in `order`, which must be at least 1.
+    /// The time index is only walked until this many have been found
+    #[serde(default)]
+    pub limit: Option<u32>,
+    /// whether to fetch from the network, or only what this agent holds locally.
+    /// Defaults to the GetOptions given to `crud!`
+    #[serde(default)]
+    pub get_strategy: Option<GetStrategy>,
+}
+
+/// the input to a fetch of the entries created from the `start` timestamp up to, but not including, the `end` timestamp
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FetchEntriesTimestampRange {
+    pub start: Timestamp,
+    pub end: Timestamp,
+    /// the order of the results, by when they were created. Defaults to oldest first
+    #[serde(default)]
+    pub order: SortOrder,
+    /// return at most this many results, the first ones in `order`, which must be at least 1.
+    /// The time index is only walked until this many have been found
+    #[serde(default)]
+    pub limit: Option<u32>,
+    /// whether to fetch from the network, or only what this agent holds locally.
+    /// Defaults to the GetOptions given to `crud!`
+    #[serde(default)]
+    pub get_strategy: Option<GetStrategy>,
+}
+
+/// the order in which entries fetched from a time index are returned, by when they were created
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    /// oldest first
+    #[default]
+    Asc,
+    /// newest first
+    Desc,
+}
+
+/// how finely entries are bucketed in a time index. Each step adds a component to the time paths,
+/// which spreads the links of a busy period over more bases, and lets queries narrow down further
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TimeIndexGranularity {
+    Day,
+    #[default]
+    Hour,
+    Minute,
+    Second,
+}
+
+/// a time index which entries are linked off of, by the time at which they are committed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeIndex {
+    /// the first component of all of the time paths
+    pub base_component: String,
+    pub granularity: TimeIndexGranularity,
+}
+
+impl From<String> for TimeIndex {
+    /// an hourly time index, as used before the granularity could be chosen
+    fn from(base_component: String) -> Self {
+        Self {
+            base_component,
+            granularity: TimeIndexGranularity::default(),
+        }
+    }
+}
+
+/// which of the two time indexes of an entry type to use. An entry is linked into the created index
+/// when it is created, and into the modified index every time it is created or updated
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TimeIndexKind {
+    #[default]
+    Created,
+    Modified,
+}
+
+impl TimeIndexKind {
+    /// the base component of this time index, for the entry type linked off of `path`
+    pub fn base_component(&self, path: &str) -> String {
+        match self {
+            TimeIndexKind::Created => format!("{}_time", path),
+            TimeIndexKind::Modified => format!("{}_modified", path),
+        }
+    }
+
+    /// the time at which the `record` was last linked into this time index
+    pub fn time_of<T>(&self, record: &WireRecord<T>) -> Timestamp {
+        match self {
+            TimeIndexKind::Created => record.created_at,
+            TimeIndexKind::Modified => record.updated_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FetchEntriesTime;
+    use crate::errors::CrudError;
+    use chrono::{TimeZone, Utc};
+    use hdk::prelude::*;
+
+    fn time(month: u32, day: u32, hour: Option<u32>, minute: Option<u32>) -> FetchEntriesTime {
+        FetchEntriesTime {
+            year: 2021,
+            month,
+            day,
+            hour,
+            minute,
+            second: None,
+        }
+    }
+
+    #[test]
+    fn test_to_date_time() {
+        let time = time(2, 28, Some(23), Some(30));
+        assert_eq!(
+            time.to_date_time(),
+            Ok(Utc.with_ymd_and_hms(2021, 2, 28, 23, 0, 0).unwrap())
+        );
+        assert_eq!(
+            time.to_precise_date_time(),
+            Ok(Utc.with_ymd_and_hms(2021, 2, 28, 23, 30, 0).unwrap())
+        );
+        assert_eq!(
+            time.bucket_end(),
+            Ok(Utc.with_ymd_and_hms(2021, 2, 28, 23, 31, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_invalid_time_is_an_error() {
+        let invalid = WasmError::from(CrudError::InvalidDateRange);
+        // the 13th month, the 31st of February and the 60th minute
+        for time in [
+            time(13, 1, None, None),
+            time(2, 31, Some(0), None),
+            time(2, 28, Some(0), Some(60)),
+        ] {
+            assert_eq!(time.to_precise_date_time(), Err(invalid.clone()));
+            assert_eq!(time.bucket_end(), Err(invalid.clone()));
         }
+        assert_eq!(time(13, 1, None, None).to_date_time(), Err(invalid.clone()));
+        assert_eq!(time(2, 31, Some(0), None).to_date_time(), Err(invalid));
     }
 }
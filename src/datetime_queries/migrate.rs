@@ -0,0 +1,181 @@
+use super::inputs::FetchEntriesTime;
+use super::utils::{
+    flat_day_path_from_date, get_last_component_string, is_time_path_link, start_of_day,
+    time_path_from_time,
+};
+use crate::modify_chain::utils::create_link_relaxed;
+use chrono::{Datelike, Duration};
+use hdk::prelude::*;
+use std::collections::HashSet;
+
+/// Link the entries which were indexed under the flat `base.Y-M-D.H` layout of time paths, that came
+/// before the hierarchical `base.YYYY.MM.DD.HH` layout, off of the matching hierarchical paths too,
+/// for every day from `start` to `end`, inclusive. The days have to be given since the flat
+/// day paths can't be discovered. The links under the flat paths are left in place.
+/// Returns how many entry links were created.
+pub fn migrate_flat_time_index<TY, E>(
+    link_type: TY,
+    link_type_filter: LinkTypeFilter,
+    base_component: String,
+    start: FetchEntriesTime,
+    end: FetchEntriesTime,
+) -> ExternResult<u32>
+where
+    ScopedLinkType: TryFrom<TY, Error = E>,
+    TY: Clone,
+    WasmError: From<E>,
+{
+    let mut migrated = 0;
+    let mut day = start.to_date_time()?.date_naive();
+    let last = end.to_date_time()?.date_naive();
+    while day <= last {
+        let flat_path = flat_day_path_from_date(
+            link_type.clone(),
+            base_component.clone(),
+            day.year(),
+            day.month(),
+            day.day(),
+        )?;
+        let time = FetchEntriesTime {
+            hour: None,
+            ..FetchEntriesTime::from_date_time(start_of_day(day))
+        };
+        migrated += migrate_links(
+            link_type.clone(),
+            link_type_filter.clone(),
+            base_component.clone(),
+            flat_path.path_entry_hash()?,
+            time,
+        )?;
+        day = day + Duration::days(1);
+    }
+    Ok(migrated)
+}
+
+/// link the entries under the flat path at `flat_path_entry_hash` off of the hierarchical
+/// path of `time`, and do the same for each of its child paths, a level deeper
+fn migrate_links<TY, E>(
+    link_type: TY,
+    link_type_filter: LinkTypeFilter,
+    base_component: String,
+    flat_path_entry_hash: EntryHash,
+    time: FetchEntriesTime,
+) -> ExternResult<u32>
+where
+    ScopedLinkType: TryFrom<TY, Error = E>,
+    TY: Clone,
+    WasmError: From<E>,
+{
+    let input = GetLinksInputBuilder::try_new(flat_path_entry_hash, link_type_filter.clone())?;
+    let (child_links, entry_links): (Vec<Link>, Vec<Link>) = get_links(input.build())?
+        .into_iter()
+        .partition(is_time_path_link);
+    let mut migrated = 0;
+    if !entry_links.is_empty() {
+        let path = time_path_from_time(link_type.clone(), base_component.clone(), &time)?;
+        path.ensure()?;
+        // so that running the migration again doesn't link the same entries twice
+        let input =
+            GetLinksInputBuilder::try_new(path.path_entry_hash()?, link_type_filter.clone())?;
+        let mut linked: HashSet<AnyLinkableHash> = get_links(input.build())?
+            .into_iter()
+            .map(|link| link.target)
+            .collect();
+        for link in entry_links {
+            if !linked.insert(link.target.clone()) {
+                continue;
+            }
+            create_link_relaxed(
+                path.path_entry_hash()?,
+                link.target,
+                link_type.clone(),
+                link.tag,
+            )?;
+            migrated += 1;
+        }
+    }
+    for link in child_links {
+        let component = match get_last_component_string(link.tag)
+            .ok()
+            .and_then(|component| component.parse::<u32>().ok())
+        {
+            Some(component) => component,
+            None => continue,
+        };
+        // the child paths are the hour, minute and second, in that order
+        let child_time = match (time.hour, time.minute, time.second) {
+            (None, _, _) => FetchEntriesTime {
+                hour: Some(component),
+                ..time.clone()
+            },
+            (Some(_), None, _) => FetchEntriesTime {
+                minute: Some(component),
+                ..time.clone()
+            },
+            (Some(_), Some(_), None) => FetchEntriesTime {
+                second: Some(component),
+                ..time.clone()
+            },
+            (Some(_), Some(_), Some(_)) => continue,
+        };
+        if let Ok(child_path_entry_hash) = EntryHash::try_from(link.target) {
+            migrated += migrate_links(
+                link_type.clone(),
+                link_type_filter.clone(),
+                base_component.clone(),
+                child_path_entry_hash,
+                child_time,
+            )?;
+        }
+    }
+    Ok(migrated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::migrate_flat_time_index;
+    use crate::datetime_queries::utils::time_path_from_time;
+    use crate::test_helpers::{
+        entry_hash, fetch_time, link, link_type_filter, set_link_store, TestLinkType,
+    };
+    use hdk::prelude::*;
+
+    #[test]
+    fn test_migrate_flat_time_index_twice() {
+        let store = set_link_store();
+        let flat_hour = Path::from("todo_time.2021-1-1.10")
+            .typed(TestLinkType)
+            .unwrap();
+        flat_hour.ensure().unwrap();
+        for n in [1, 2] {
+            store.add(link(
+                flat_hour.path_entry_hash().unwrap().into(),
+                entry_hash(n).into(),
+                0,
+                LinkTag::new("entry"),
+            ));
+        }
+        let migrate = || {
+            migrate_flat_time_index(
+                TestLinkType,
+                link_type_filter(),
+                String::from("todo_time"),
+                fetch_time(2021, 1, 1, None),
+                fetch_time(2021, 1, 1, None),
+            )
+        };
+        assert_eq!(migrate(), Ok(2));
+        // the entries are already linked off of the hierarchical path
+        assert_eq!(migrate(), Ok(0));
+        let hour_path = time_path_from_time(
+            TestLinkType,
+            String::from("todo_time"),
+            &fetch_time(2021, 1, 1, Some(10)),
+        )
+        .unwrap();
+        assert_eq!(
+            store.targets(&hour_path.path_entry_hash().unwrap().into()),
+            vec![entry_hash(1).into(), entry_hash(2).into()]
+        );
+    }
+}
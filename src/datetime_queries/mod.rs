@@ -8,4 +8,5 @@ pub mod fetch_entries_from_hour_to_hour;
 pub mod fetch_in_time_range;
 pub mod fetchers;
 pub mod inputs;
+pub mod migrate;
 pub mod utils;
@@ -1,35 +1,55 @@
-use super::inputs::FetchEntriesTime;
-use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use super::inputs::{FetchEntriesTime, SortOrder, TimeIndexGranularity, TimeIndexKind};
+use crate::errors::CrudError;
+use crate::wire_record::WireRecord;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc};
+use hdk::hash_path::path::DHT_PREFIX;
 use hdk::prelude::*;
 
 pub fn is_valid_date_range(
     start: FetchEntriesTime,
     end: FetchEntriesTime,
 ) -> Result<(), WasmError> {
-    match start.to_date_time() < end.to_date_time() {
+    match start.to_precise_date_time()? < end.to_precise_date_time()? {
         // Here is where we could allow for start and end to be equal
         true => Ok(()),
-        false => Err(err("invalid date range")),
+        false => Err(CrudError::InvalidDateRange.into()),
     }
 }
-pub fn next_day(date_time: DateTime<Utc>) -> DateTime<Utc> {
-    let next_day = date_time + Duration::days(1);
-    DateTime::from_utc(
-        NaiveDate::from_ymd(next_day.year(), next_day.month(), next_day.day()).and_hms(0, 0, 0),
-        Utc,
-    )
+/// the first moment of the day after the one `date_time` falls on.
+/// Errors with [CrudError::InvalidDateRange] past the last day chrono can represent
+pub fn next_day(date_time: DateTime<Utc>) -> ExternResult<DateTime<Utc>> {
+    match date_time.date_naive().succ_opt() {
+        Some(day) => Ok(start_of_day(day)),
+        None => Err(CrudError::InvalidDateRange.into()),
+    }
 }
 
+/// the UTC date time of a `timestamp`, such as the one of an action.
+/// Errors with [CrudError::InvalidDateRange] if it is beyond the years chrono can represent
+pub fn date_time_from_timestamp(timestamp: Timestamp) -> ExternResult<DateTime<Utc>> {
+    let (secs, nanos) = timestamp.as_seconds_and_nanos();
+    DateTime::from_timestamp(secs, nanos).ok_or_else(|| CrudError::InvalidDateRange.into())
+}
+
+/// the first moment of the `day`
+pub fn start_of_day(day: NaiveDate) -> DateTime<Utc> {
+    day.and_time(NaiveTime::MIN).and_utc()
+}
+
+/// a free-form guest error. Prefer a [CrudError], which clients can tell apart by its code
 pub fn err(reason: &str) -> WasmError {
     wasm_error!(WasmErrorInner::Guest(String::from(reason)))
 }
 pub fn serialize_err(sbe: SerializedBytesError) -> WasmError {
-    wasm_error!(WasmErrorInner::Serialize(sbe))
+    CrudError::from(sbe).into()
 }
 
 /// used to convert the last component of a path (in this case, the hour of a day) into a string
 pub fn get_last_component_string(path_tag: LinkTag) -> ExternResult<String> {
-    let component_bytes = &path_tag.0[1..];
+    let component_bytes = match path_tag.0.get(1..) {
+        Some(component_bytes) => component_bytes,
+        None => return Err(CrudError::InvalidPath(String::new()).into()),
+    };
     let component: Component = SerializedBytes::from(UnsafeBytes::from(component_bytes.to_vec()))
         .try_into()
         .map_err(serialize_err)?;
@@ -37,6 +57,7 @@ pub fn get_last_component_string(path_tag: LinkTag) -> ExternResult<String> {
     Ok(hour_str)
 }
 
+/// the path of a day in the time index, `base.YYYY.MM.DD`
 pub fn day_path_from_date<TY, E>(
     link_type: TY,
     base_component: String,
@@ -48,9 +69,21 @@ where
     ScopedLinkType: TryFrom<TY, Error = E>,
     WasmError: From<E>,
 {
-    Path::from(format!("{}.{}-{}-{}", base_component, year, month, day)).typed(link_type)
+    time_path_from_time(
+        link_type,
+        base_component,
+        &FetchEntriesTime {
+            year,
+            month,
+            day,
+            hour: None,
+            minute: None,
+            second: None,
+        },
+    )
 }
 
+/// the path of an hour of a day in the time index, `base.YYYY.MM.DD.HH`
 pub fn hour_path_from_date<TY, E>(
     link_type: TY,
     base_component: String,
@@ -63,9 +96,279 @@ where
     ScopedLinkType: TryFrom<TY, Error = E>,
     WasmError: From<E>,
 {
-    Path::from(format!(
-        "{}.{}-{}-{}.{}",
-        base_component, year, month, day, hour
+    time_path_from_time(
+        link_type,
+        base_component,
+        &FetchEntriesTime {
+            year,
+            month,
+            day,
+            hour: Some(hour),
+            minute: None,
+            second: None,
+        },
+    )
+}
+
+/// the path of a day in the flat `base.Y-M-D` layout of time indexes,
+/// which came before the `base.YYYY.MM.DD` layout. Only needed to migrate from it
+pub fn flat_day_path_from_date<TY, E>(
+    link_type: TY,
+    base_component: String,
+    year: i32,
+    month: u32,
+    day: u32,
+) -> ExternResult<TypedPath>
+where
+    ScopedLinkType: TryFrom<TY, Error = E>,
+    WasmError: From<E>,
+{
+    Path::from(format!("{}.{}-{}-{}", base_component, year, month, day)).typed(link_type)
+}
+
+/// the time path for the bucket that `time` selects: `base.YYYY.MM.DD`, followed by its hour,
+/// minute and second, as far as they are given. Every level is its own component,
+/// zero padded so that the components sort in chronological order
+pub fn time_path_from_time<TY, E>(
+    link_type: TY,
+    base_component: String,
+    time: &FetchEntriesTime,
+) -> ExternResult<TypedPath>
+where
+    ScopedLinkType: TryFrom<TY, Error = E>,
+    WasmError: From<E>,
+{
+    let mut components = vec![
+        base_component,
+        format!("{:04}", time.year),
+        format!("{:02}", time.month),
+        format!("{:02}", time.day),
+    ];
+    if let Some(hour) = time.hour {
+        components.push(format!("{:02}", hour));
+        if let Some(minute) = time.minute {
+            components.push(format!("{:02}", minute));
+            if let Some(second) = time.second {
+                components.push(format!("{:02}", second));
+            }
+        }
+    }
+    Path::from(components.join(".")).typed(link_type)
+}
+
+/// the numbers which the child paths of `path` are named by, in order.
+/// Children which aren't numbers are skipped
+fn child_numbers(path: &TypedPath, get_strategy: GetStrategy) -> ExternResult<Vec<u32>> {
+    let link_type_filter =
+        LinkTypeFilter::single_type(path.link_type.zome_index, path.link_type.zome_type);
+    let input = GetLinksInputBuilder::try_new(path.path_entry_hash()?, link_type_filter)?
+        .get_options(get_strategy);
+    let mut numbers = get_links(input.build())?
+        .into_iter()
+        .filter(is_time_path_link)
+        .filter_map(|link| get_last_component_string(link.tag).ok())
+        .filter_map(|component| component.parse::<u32>().ok())
+        .collect::<Vec<u32>>();
+    numbers.sort_unstable();
+    numbers.dedup();
+    Ok(numbers)
+}
+
+/// the days from `first` to `last`, inclusive, which have a path in the time index.
+/// They are found by walking down the years and months of the index,
+/// so that the months and days without any entries are skipped entirely
+pub fn existing_days<TY, E>(
+    link_type: TY,
+    base_component: String,
+    first: NaiveDate,
+    last: NaiveDate,
+    get_strategy: GetStrategy,
+) -> ExternResult<Vec<NaiveDate>>
+where
+    ScopedLinkType: TryFrom<TY, Error = E>,
+    TY: Clone,
+    WasmError: From<E>,
+{
+    let mut days = Vec::new();
+    let base = Path::from(base_component.clone()).typed(link_type.clone())?;
+    for year in child_numbers(&base, get_strategy)? {
+        let year = year as i32;
+        if year < first.year() || year > last.year() {
+            continue;
+        }
+        let year_path =
+            Path::from(format!("{}.{:04}", base_component, year)).typed(link_type.clone())?;
+        for month in child_numbers(&year_path, get_strategy)? {
+            if (year, month) < (first.year(), first.month())
+                || (year, month) > (last.year(), last.month())
+            {
+                continue;
+            }
+            let month_path = Path::from(format!("{}.{:04}.{:02}", base_component, year, month))
+                .typed(link_type.clone())?;
+            for day in child_numbers(&month_path, get_strategy)? {
+                match NaiveDate::from_ymd_opt(year, month, day) {
+                    Some(date) if date >= first && date <= last => days.push(date),
+                    _ => (),
+                }
+            }
+        }
+    }
+    Ok(days)
+}
+
+/// the time path for the bucket of `granularity` which `date` falls in
+pub fn time_path_from_date<TY, E>(
+    link_type: TY,
+    base_component: String,
+    date: DateTime<Utc>,
+    granularity: TimeIndexGranularity,
+) -> ExternResult<TypedPath>
+where
+    ScopedLinkType: TryFrom<TY, Error = E>,
+    WasmError: From<E>,
+{
+    time_path_from_time(
+        link_type,
+        base_component,
+        &FetchEntriesTime::from_date_time_with_granularity(date, granularity),
+    )
+}
+
+/// whether the link points from a time path to one of its child paths, rather than to an entry
+pub fn is_time_path_link(link: &Link) -> bool {
+    link.tag.0.first() == Some(&DHT_PREFIX)
+}
+
+/// the targets of all of the entry links under the time path at `path_entry_hash`.
+/// Child time paths are descended into, so that the entries are found whatever
+/// the granularity of the time index they were linked off of.
+pub fn get_time_path_targets(
+    path_entry_hash: EntryHash,
+    link_type_filter: LinkTypeFilter,
+    get_strategy: GetStrategy,
+) -> ExternResult<Vec<AnyLinkableHash>> {
+    let input = GetLinksInputBuilder::try_new(path_entry_hash, link_type_filter.clone())?
+        .get_options(get_strategy);
+    let mut targets = Vec::new();
+    let mut child_paths = Vec::new();
+    for link in get_links(input.build())? {
+        match is_time_path_link(&link) {
+            true => {
+                if let Ok(child_path_entry_hash) = EntryHash::try_from(link.target) {
+                    child_paths.push(child_path_entry_hash);
+                }
+            }
+            false => targets.push(link.target),
+        }
+    }
+    // every agent that indexes into a path links it to its parent again
+    child_paths.sort();
+    child_paths.dedup();
+    for child_path_entry_hash in child_paths {
+        targets.extend(get_time_path_targets(
+            child_path_entry_hash,
+            link_type_filter.clone(),
+            get_strategy,
+        )?);
+    }
+    Ok(targets)
+}
+
+/// the window of time, in UTC, from the start of `start` up to the end of `end`,
+/// when they are given in the local time at `utc_offset` minutes from UTC
+pub fn local_window_to_utc(
+    start: &FetchEntriesTime,
+    end: &FetchEntriesTime,
+    utc_offset: i32,
+) -> ExternResult<(DateTime<Utc>, DateTime<Utc>)> {
+    let offset = Duration::minutes(utc_offset as i64);
+    Ok((
+        start.to_precise_date_time()? - offset,
+        end.bucket_end()? - offset,
     ))
-    .typed(link_type)
+}
+
+/// keep only the records whose time in the `kind` of time index is from `start` up to, but not including, `end`
+pub fn filter_within<T>(
+    records: Vec<WireRecord<T>>,
+    kind: TimeIndexKind,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Vec<WireRecord<T>> {
+    let (start, end) = (start.timestamp_micros(), end.timestamp_micros());
+    records
+        .into_iter()
+        .filter(|record| {
+            let time = kind.time_of(record).as_micros();
+            time >= start && time < end
+        })
+        .collect()
+}
+
+/// sort the records by their time in the `kind` of time index, in the given `order`
+pub fn sort_by_time<T>(records: &mut [WireRecord<T>], kind: TimeIndexKind, order: SortOrder) {
+    records.sort_by_key(|record| kind.time_of(record));
+    if order == SortOrder::Desc {
+        records.reverse();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{existing_days, next_day};
+    use crate::errors::CrudError;
+    use crate::test_helpers::{fake_hash, index_day, set_link_store, TestLinkType};
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use hdk::prelude::*;
+
+    #[test]
+    fn test_next_day() {
+        assert_eq!(
+            next_day(Utc.with_ymd_and_hms(2021, 2, 28, 23, 30, 0).unwrap()),
+            Ok(Utc.with_ymd_and_hms(2021, 3, 1, 0, 0, 0).unwrap())
+        );
+        // there is no day after the last one chrono can represent
+        let last = NaiveDate::MAX.and_hms_opt(0, 0, 0).unwrap().and_utc();
+        assert_eq!(
+            next_day(last),
+            Err(WasmError::from(CrudError::InvalidDateRange))
+        );
+    }
+
+    #[test]
+    fn test_existing_days() {
+        let store = set_link_store();
+        for (year, month, day) in [(2021, 10, 20), (2021, 10, 25), (2021, 11, 1), (2022, 1, 1)] {
+            index_day("todo_time", year, month, day);
+        }
+        // walk the index with the get strategy which is asked for
+        let mut mock_hdk = MockHdkT::new();
+        mock_hdk.expect_hash().returning(fake_hash);
+        mock_hdk
+            .expect_get_links()
+            .withf(|inputs| {
+                inputs
+                    .iter()
+                    .all(|input| input.get_options.strategy == GetStrategy::Local)
+            })
+            .returning(move |inputs| {
+                Ok(inputs
+                    .into_iter()
+                    .map(|input| store.get(&input.base_address, input.tag_prefix.as_ref()))
+                    .collect())
+            });
+        set_hdk(mock_hdk);
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+        assert_eq!(
+            existing_days(
+                TestLinkType,
+                "todo_time".to_string(),
+                date(2021, 10, 21),
+                date(2021, 12, 31),
+                GetStrategy::Local,
+            ),
+            Ok(vec![date(2021, 10, 25), date(2021, 11, 1)])
+        );
+    }
 }
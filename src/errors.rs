@@ -0,0 +1,123 @@
+use crate::retrieval::outputs::{FetchFailure, FetchFailureReason};
+use hdk::prelude::*;
+use holo_hash::{ActionHashB64, AgentPubKeyB64, AnyLinkableHashB64};
+
+/// The errors returned by hdk_crud, and by the zome functions generated by [crud!](crate::crud!),
+/// beyond the ones coming straight from the host.
+///
+/// They reach the client as a `WasmErrorInner::Guest` holding `"<CODE>: <message>"`,
+/// where `<CODE>` is the stable [code](CrudError::code) of the variant,
+/// so that clients can branch on the kind of error instead of on the message.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum CrudError {
+    /// nothing could be retrieved at the hash
+    #[error("{0} could not be found")]
+    NotFound(AnyLinkableHashB64),
+    /// the entry at the hash has been deleted
+    #[error("{0} has been deleted")]
+    Deleted(AnyLinkableHashB64),
+    /// the entry at the hash is not of the expected entry type
+    #[error("{0} is not of the expected entry type")]
+    WrongEntryType(AnyLinkableHashB64),
+    /// the hash, such as the target of a link, is not the hash of an entry
+    #[error("{0} is not an entry")]
+    NotAnEntry(AnyLinkableHashB64),
+    /// a page, or the results of a time range, were asked for with a limit of zero
+    #[error("the limit of a page must be at least 1")]
+    InvalidPageLimit,
+    /// the start of a date range is not before its end
+    #[error("invalid date range")]
+    InvalidDateRange,
+    /// a component of a time index path could not be read
+    #[error("invalid path component {0}")]
+    InvalidPath(String),
+    /// the caller tried to update or delete an entry which was authored by another agent,
+    /// while in [Ownership::AuthorOnly](crate::modify_chain::ownership::Ownership::AuthorOnly) mode
+    #[error("agent {caller} is not the owner of {action_hash}, which was authored by {author}")]
+    NotOwner {
+        action_hash: ActionHashB64,
+        author: AgentPubKeyB64,
+        caller: AgentPubKeyB64,
+    },
+    /// something could not be serialized or deserialized
+    #[error("serialization failed: {0}")]
+    Serialization(String),
+    /// a call to the host failed
+    #[error("unavailable: {0}")]
+    Unavailable(String),
+    /// a received signal is not one of the signal types of the zome
+    #[error("unknown or malformed signal: {0}")]
+    UnknownSignal(String),
+    /// a signal was received from an agent who is not allowed to send it
+    #[error("signal from {0} was rejected")]
+    UnverifiedSender(AgentPubKeyB64),
+}
+
+impl CrudError {
+    /// the stable, machine readable, code of the error, which prefixes its message
+    pub fn code(&self) -> &'static str {
+        match self {
+            CrudError::NotFound(_) => "NOT_FOUND",
+            CrudError::Deleted(_) => "DELETED",
+            CrudError::WrongEntryType(_) => "WRONG_ENTRY_TYPE",
+            CrudError::NotAnEntry(_) => "NOT_AN_ENTRY",
+            CrudError::InvalidPageLimit => "INVALID_PAGE_LIMIT",
+            CrudError::InvalidDateRange => "INVALID_DATE_RANGE",
+            CrudError::InvalidPath(_) => "INVALID_PATH",
+            CrudError::NotOwner { .. } => "NOT_OWNER",
+            CrudError::Serialization(_) => "SERIALIZATION",
+            CrudError::Unavailable(_) => "UNAVAILABLE",
+            CrudError::UnknownSignal(_) => "UNKNOWN_SIGNAL",
+            CrudError::UnverifiedSender(_) => "UNVERIFIED_SENDER",
+        }
+    }
+}
+
+impl From<CrudError> for WasmError {
+    fn from(error: CrudError) -> Self {
+        wasm_error!(WasmErrorInner::Guest(format!(
+            "{}: {}",
+            error.code(),
+            error
+        )))
+    }
+}
+
+impl From<SerializedBytesError> for CrudError {
+    fn from(error: SerializedBytesError) -> Self {
+        CrudError::Serialization(error.to_string())
+    }
+}
+
+impl From<FetchFailure> for CrudError {
+    fn from(failure: FetchFailure) -> Self {
+        match failure.reason {
+            FetchFailureReason::NotFound => CrudError::NotFound(failure.hash),
+            FetchFailureReason::Deleted => CrudError::Deleted(failure.hash),
+            FetchFailureReason::NotAnEntry => CrudError::NotAnEntry(failure.hash),
+            FetchFailureReason::WrongType => CrudError::WrongEntryType(failure.hash),
+            FetchFailureReason::Deserialize(message) => CrudError::Serialization(message),
+            FetchFailureReason::Unavailable(message) => CrudError::Unavailable(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CrudError;
+    use hdk::prelude::*;
+
+    #[test]
+    fn test_crud_error_into_wasm_error() {
+        let error = WasmError::from(CrudError::InvalidDateRange);
+        assert_eq!(
+            error.error,
+            WasmErrorInner::Guest(String::from("INVALID_DATE_RANGE: invalid date range"))
+        );
+        let error = WasmError::from(CrudError::InvalidPath(String::from("ten")));
+        assert_eq!(
+            error.error,
+            WasmErrorInner::Guest(String::from("INVALID_PATH: invalid path component ten"))
+        );
+    }
+}
@@ -1,6 +1,10 @@
 pub mod modify_chain;
 pub mod crud;
 pub mod datetime_queries;
+pub mod errors;
 pub mod retrieval;
 pub mod signals;
+pub mod validation;
 pub mod wire_record;
+#[cfg(test)]
+mod test_helpers;
@@ -1,12 +1,14 @@
+use crate::datetime_queries::inputs::TimeIndex;
+use crate::modify_chain::utils::add_time_path;
+use crate::signals::{SignalData, SignalPayload};
 use crate::wire_record::WireRecord;
-use crate::{datetime_queries::utils::serialize_err, modify_chain::utils::add_current_time_path};
 use hdk::prelude::*;
-use holo_hash::{ActionHashB64, AgentPubKey, EntryHashB64};
+use holo_hash::{ActionHashB64, AgentPubKey, AgentPubKeyB64, EntryHashB64};
 
 #[cfg(feature = "mock")]
 use ::mockall::automock;
 
-use super::utils::create_link_relaxed;
+use super::utils::{create_link_relaxed, get_signed_action, send_action_signal};
 
 /// an enum passed into do_create to indicate whether the newly created entry is to be
 /// linked off a path (like an anchor for entry types) or a supplied entry hash
@@ -23,8 +25,12 @@ pub struct DoCreate {}
 #[cfg_attr(feature = "mock", automock)]
 impl DoCreate {
     /// This will create an entry and will either link it off the main Path or a supplied entry hash.
-    /// It can also optionally send a signal of this event to all peers supplied in `send_signal_to_peers`
+    /// It can also optionally send a signal of this event to all peers supplied in `send_signal_to_peers`,
+    /// or in `send_signal_with_secrets` along with the cap secret of each of them,
+    /// and, if `emit_locally`, emit it to the UIs of this agent as well
     /// uses `ChainTopOrdering::Relaxed` such that multiple creates can be committed in parallel
+    /// The entry is also linked off of the current time path of each of the `time_indexes`
+    /// How much of the created record the signal carries is decided by the `signal_payload`
     pub fn do_create<MyEntryTypes, CrudType, E, S, R>(
         &self,
         full_entry: MyEntryTypes,
@@ -33,7 +39,10 @@ impl DoCreate {
         entry_type_id: String,
         scoped_link_type: R,
         send_signal_to_peers: Option<Vec<AgentPubKey>>,
-        add_time_path: Option<String>,
+        send_signal_with_secrets: Option<Vec<(AgentPubKey, CapSecret)>>,
+        time_indexes: Vec<TimeIndex>,
+        signal_payload: SignalPayload,
+        emit_locally: bool,
     ) -> ExternResult<WireRecord<CrudType>>
     where
         CrudType: Clone,
@@ -51,79 +60,172 @@ impl DoCreate {
             + std::fmt::Debug,
         E: 'static,
     {
-        // calling create instead of create_entry to be able to indicate relaxed chain ordering
-        let ScopedEntryDefIndex {
-            zome_index,
-            zome_type: entry_def_index,
-        } = (&full_entry).try_into()?;
-        let visibility = EntryVisibility::from(&full_entry);
-        let address = create(CreateInput::new(
-            EntryDefLocation::app(zome_index, entry_def_index),
-            visibility,
-            full_entry.clone().try_into()?,
-            ChainTopOrdering::Relaxed,
-        ))?;
-        let entry_hash = hash_entry(full_entry.clone())?;
-        match link_off {
-            None => (), //no link is made
-            Some(path_or_entry_hash) => match path_or_entry_hash {
-                TypedPathOrEntryHash::TypedPath(path) => {
-                    // link off entry path
-                    path.ensure()?;
-                    let path_hash = path.path_entry_hash()?;
-                    create_link_relaxed(
-                        path_hash,
-                        entry_hash.clone(),
-                        scoped_link_type.clone(),
-                        LinkTag::from(vec![]),
-                    )?;
-                }
-                TypedPathOrEntryHash::EntryHash(base_entry_hash) => {
-                    // link off supplied entry hash
-                    create_link_relaxed(
-                        base_entry_hash,
-                        entry_hash.clone(),
-                        scoped_link_type.clone(),
-                        LinkTag::from(vec![]),
-                    )?;
-                }
-            },
+        let wire_entry = create_entry::<MyEntryTypes, CrudType, E, R>(
+            full_entry,
+            inner_entry,
+            link_off,
+            scoped_link_type,
+            time_indexes,
+        )?;
+        if send_signal_to_peers.is_some() || send_signal_with_secrets.is_some() || emit_locally {
+            let action_signal: crate::signals::ActionSignal<CrudType> =
+                crate::signals::ActionSignal {
+                    entry_type: entry_type_id,
+                    action: crate::signals::ActionType::Create,
+                    data: SignalData::created(wire_entry.clone(), signal_payload),
+                };
+            send_action_signal::<CrudType, S>(
+                action_signal,
+                send_signal_to_peers,
+                send_signal_with_secrets,
+                emit_locally,
+            )?;
         }
-        match add_time_path {
-            None => (),
-            Some(base_component) => {
-                // create a time_path
-                add_current_time_path(
-                    base_component,
+        Ok(wire_entry)
+    }
+
+    /// This will create many entries at once, each just like [do_create](DoCreate::do_create) would,
+    /// but all in a single zome call. Instead of one signal per entry, it will optionally send a
+    /// single `SignalData::CreateBatch` signal to all peers supplied in `send_signal_to_peers`
+    /// or `send_signal_with_secrets`,
+    /// and emit it locally if `emit_locally`
+    pub fn do_create_batch<MyEntryTypes, CrudType, E, S, R>(
+        &self,
+        entries: Vec<(MyEntryTypes, CrudType)>,
+        link_off: Option<TypedPathOrEntryHash>,
+        entry_type_id: String,
+        scoped_link_type: R,
+        send_signal_to_peers: Option<Vec<AgentPubKey>>,
+        send_signal_with_secrets: Option<Vec<(AgentPubKey, CapSecret)>>,
+        time_indexes: Vec<TimeIndex>,
+        signal_payload: SignalPayload,
+        emit_locally: bool,
+    ) -> ExternResult<Vec<WireRecord<CrudType>>>
+    where
+        CrudType: Clone,
+        ScopedEntryDefIndex: for<'a> TryFrom<&'a MyEntryTypes, Error = E>,
+        EntryVisibility: for<'a> From<&'a MyEntryTypes>,
+        Entry: 'static + TryFrom<MyEntryTypes, Error = E>,
+        ScopedLinkType: TryFrom<R, Error = E>,
+        R: Clone,
+        WasmError: From<E>,
+        MyEntryTypes: 'static + Clone,
+        AppEntryBytes: TryFrom<MyEntryTypes, Error = E>,
+        S: 'static
+            + From<crate::signals::ActionSignal<CrudType>>
+            + serde::Serialize
+            + std::fmt::Debug,
+        E: 'static,
+    {
+        let wire_entries = entries
+            .into_iter()
+            .map(|(full_entry, inner_entry)| {
+                create_entry::<MyEntryTypes, CrudType, E, R>(
+                    full_entry,
+                    inner_entry,
+                    link_off.clone(),
+                    scoped_link_type.clone(),
+                    time_indexes.clone(),
+                )
+            })
+            .collect::<ExternResult<Vec<WireRecord<CrudType>>>>()?;
+        if send_signal_to_peers.is_some() || send_signal_with_secrets.is_some() || emit_locally {
+            let action_signal: crate::signals::ActionSignal<CrudType> =
+                crate::signals::ActionSignal {
+                    entry_type: entry_type_id,
+                    action: crate::signals::ActionType::Create,
+                    data: SignalData::created_batch(wire_entries.clone(), signal_payload),
+                };
+            send_action_signal::<CrudType, S>(
+                action_signal,
+                send_signal_to_peers,
+                send_signal_with_secrets,
+                emit_locally,
+            )?;
+        }
+        Ok(wire_entries)
+    }
+}
+
+/// create the entry, and the links to it, that [do_create](DoCreate::do_create) and
+/// [do_create_batch](DoCreate::do_create_batch) are responsible for, without signaling
+fn create_entry<MyEntryTypes, CrudType, E, R>(
+    full_entry: MyEntryTypes,
+    inner_entry: CrudType,
+    link_off: Option<TypedPathOrEntryHash>,
+    scoped_link_type: R,
+    time_indexes: Vec<TimeIndex>,
+) -> ExternResult<WireRecord<CrudType>>
+where
+    ScopedEntryDefIndex: for<'a> TryFrom<&'a MyEntryTypes, Error = E>,
+    EntryVisibility: for<'a> From<&'a MyEntryTypes>,
+    Entry: TryFrom<MyEntryTypes, Error = E>,
+    ScopedLinkType: TryFrom<R, Error = E>,
+    R: Clone,
+    WasmError: From<E>,
+    MyEntryTypes: Clone,
+{
+    // calling create instead of create_entry to be able to indicate relaxed chain ordering
+    let ScopedEntryDefIndex {
+        zome_index,
+        zome_type: entry_def_index,
+    } = (&full_entry).try_into()?;
+    let visibility = EntryVisibility::from(&full_entry);
+    let address = create(CreateInput::new(
+        EntryDefLocation::app(zome_index, entry_def_index),
+        visibility,
+        full_entry.clone().try_into()?,
+        ChainTopOrdering::Relaxed,
+    ))?;
+    let entry_hash = hash_entry(full_entry.clone())?;
+    match link_off {
+        None => (), //no link is made
+        Some(path_or_entry_hash) => match path_or_entry_hash {
+            TypedPathOrEntryHash::TypedPath(path) => {
+                // link off entry path
+                path.ensure()?;
+                let path_hash = path.path_entry_hash()?;
+                create_link_relaxed(
+                    path_hash,
                     entry_hash.clone(),
-                    scoped_link_type,
+                    scoped_link_type.clone(),
                     LinkTag::from(vec![]),
                 )?;
             }
-        }
-        let time = sys_time()?; // this won't exactly match the timestamp stored in the record details
-        let wire_entry: WireRecord<CrudType> = WireRecord {
-            entry: inner_entry,
-            action_hash: ActionHashB64::new(address),
-            entry_hash: EntryHashB64::new(entry_hash),
-            created_at: time,
-            updated_at: time,
-        };
-
-        match send_signal_to_peers {
-            None => (),
-            Some(vec_peers) => {
-                let action_signal: crate::signals::ActionSignal<CrudType> =
-                    crate::signals::ActionSignal {
-                        entry_type: entry_type_id,
-                        action: crate::signals::ActionType::Create,
-                        data: crate::signals::SignalData::Create(wire_entry.clone()),
-                    };
-                let signal = S::from(action_signal);
-                let payload = ExternIO::encode(signal).map_err(serialize_err)?;
-                send_remote_signal(payload, vec_peers)?;
+            TypedPathOrEntryHash::EntryHash(base_entry_hash) => {
+                // link off supplied entry hash
+                create_link_relaxed(
+                    base_entry_hash,
+                    entry_hash.clone(),
+                    scoped_link_type.clone(),
+                    LinkTag::from(vec![]),
+                )?;
             }
-        }
-        Ok(wire_entry)
+        },
+    }
+    // read the action back so that the timestamp matches the one stored in the record exactly
+    let action = get_signed_action(address.clone(), GetOptions::local())?;
+    let time = action.action().timestamp();
+    for time_index in time_indexes {
+        // create a time_path
+        add_time_path(
+            time_index,
+            entry_hash.clone(),
+            scoped_link_type.clone(),
+            LinkTag::from(vec![]),
+            time,
+        )?;
     }
+    let author = AgentPubKeyB64::new(action.action().author().to_owned());
+    let wire_entry: WireRecord<CrudType> = WireRecord {
+        entry: inner_entry,
+        action_hash: ActionHashB64::new(address.clone()),
+        entry_hash: EntryHashB64::new(entry_hash),
+        created_at: time,
+        updated_at: time,
+        created_by: Some(author.clone()),
+        updated_by: Some(author),
+        latest_action_hash: Some(ActionHashB64::new(address)),
+    };
+    Ok(wire_entry)
 }
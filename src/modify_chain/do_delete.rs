@@ -4,7 +4,8 @@ use holo_hash::{ActionHashB64, AgentPubKey};
 #[cfg(feature = "mock")]
 use ::mockall::automock;
 
-use crate::datetime_queries::utils::serialize_err;
+use crate::modify_chain::ownership::{check_ownership, Ownership};
+use crate::modify_chain::utils::send_action_signal;
 
 /// a struct which implements a [do_delete](DoDelete::do_delete) method
 /// a method is used instead of a function so that it can be mocked to simplify unit testing
@@ -13,13 +14,19 @@ pub struct DoDelete {}
 #[cfg_attr(feature = "mock", automock)]
 impl DoDelete {
     /// This will mark the entry at `address` as "deleted".
-    /// It can also optionally send a signal of this event to all peers supplied in `send_signal_to_peers`
+    /// It can also optionally send a signal of this event to all peers supplied in `send_signal_to_peers`,
+    /// or in `send_signal_with_secrets` along with the cap secret of each of them,
     /// to all peers returned by the `get_peers` call given during the macro call to `crud!`
+    /// and, if `emit_locally`, emit it to the UIs of this agent as well
+    /// The calling agent has to be allowed to delete the entry by `ownership`
     pub fn do_delete<T, E, S>(
         &self,
         action_hash: ActionHashB64,
         entry_type_id: String,
         send_signal_to_peers: Option<Vec<AgentPubKey>>,
+        send_signal_with_secrets: Option<Vec<(AgentPubKey, CapSecret)>>,
+        ownership: Ownership,
+        emit_locally: bool,
     ) -> ExternResult<ActionHashB64>
     where
         Entry: 'static + TryFrom<T, Error = E>,
@@ -29,23 +36,179 @@ impl DoDelete {
         S: 'static + From<crate::signals::ActionSignal<T>> + serde::Serialize + std::fmt::Debug,
         E: 'static,
     {
+        check_ownership(&ownership, &action_hash)?;
         delete_entry(DeleteInput::new(
             action_hash.clone().into(),
             ChainTopOrdering::Relaxed,
         ))?;
-        match send_signal_to_peers {
-            None => (),
-            Some(vec_peers) => {
-                let action_signal: crate::signals::ActionSignal<T> = crate::signals::ActionSignal {
-                    entry_type: entry_type_id,
-                    action: crate::signals::ActionType::Delete,
-                    data: crate::signals::SignalData::Delete::<T>(action_hash.clone()),
-                };
-                let signal = S::from(action_signal);
-                let payload = ExternIO::encode(signal).map_err(serialize_err)?;
-                send_remote_signal(payload, vec_peers)?;
-            }
+        if send_signal_to_peers.is_some() || send_signal_with_secrets.is_some() || emit_locally {
+            let action_signal: crate::signals::ActionSignal<T> = crate::signals::ActionSignal {
+                entry_type: entry_type_id,
+                action: crate::signals::ActionType::Delete,
+                data: crate::signals::SignalData::Delete::<T>(action_hash.clone()),
+            };
+            send_action_signal::<T, S>(
+                action_signal,
+                send_signal_to_peers,
+                send_signal_with_secrets,
+                emit_locally,
+            )?;
         }
         Ok(action_hash)
     }
+
+    /// This will mark many entries as "deleted" at once, each just like [do_delete](DoDelete::do_delete) would,
+    /// but all in a single zome call. Instead of one signal per entry, it will optionally send a
+    /// single `SignalData::DeleteBatch` signal to all peers supplied in `send_signal_to_peers`
+    /// or `send_signal_with_secrets`,
+    /// and emit it locally if `emit_locally`.
+    /// Nothing is deleted unless the calling agent is allowed to delete every one of the entries by `ownership`
+    pub fn do_delete_batch<T, E, S>(
+        &self,
+        action_hashes: Vec<ActionHashB64>,
+        entry_type_id: String,
+        send_signal_to_peers: Option<Vec<AgentPubKey>>,
+        send_signal_with_secrets: Option<Vec<(AgentPubKey, CapSecret)>>,
+        ownership: Ownership,
+        emit_locally: bool,
+    ) -> ExternResult<Vec<ActionHashB64>>
+    where
+        Entry: 'static + TryFrom<T, Error = E>,
+        WasmError: 'static + From<E>,
+        T: 'static + Clone,
+        AppEntryBytes: 'static + TryFrom<T, Error = E>,
+        S: 'static + From<crate::signals::ActionSignal<T>> + serde::Serialize + std::fmt::Debug,
+        E: 'static,
+    {
+        for action_hash in action_hashes.iter() {
+            check_ownership(&ownership, action_hash)?;
+        }
+        for action_hash in action_hashes.iter() {
+            delete_entry(DeleteInput::new(
+                action_hash.clone().into(),
+                ChainTopOrdering::Relaxed,
+            ))?;
+        }
+        if send_signal_to_peers.is_some() || send_signal_with_secrets.is_some() || emit_locally {
+            let action_signal: crate::signals::ActionSignal<T> = crate::signals::ActionSignal {
+                entry_type: entry_type_id,
+                action: crate::signals::ActionType::Delete,
+                data: crate::signals::SignalData::DeleteBatch::<T>(action_hashes.clone()),
+            };
+            send_action_signal::<T, S>(
+                action_signal,
+                send_signal_to_peers,
+                send_signal_with_secrets,
+                emit_locally,
+            )?;
+        }
+        Ok(action_hashes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DoDelete;
+    use crate::crud::example::Example;
+    use crate::errors::CrudError;
+    use crate::modify_chain::ownership::Ownership;
+    use crate::signals::{ActionSignal, ActionType, SignalData};
+    use crate::test_helpers::*;
+    use hdk::prelude::*;
+    use holo_hash::ActionHashB64;
+    use std::sync::{Arc, Mutex};
+
+    /// set a mocked hdk, called by `agent(1)`, where entry 1 was created by `agent(1)`
+    /// and entry 2 by `agent(2)`. The deleted action hashes and the signals emitted locally
+    /// are kept in the returned lists
+    fn set_delete_hdk() -> (Arc<Mutex<Vec<ActionHash>>>, Arc<Mutex<Vec<AppSignal>>>) {
+        let mut mock_hdk = MockHdkT::new();
+        let store = RecordStore::default();
+        store.install(&mut mock_hdk);
+        store.add(example_record(create_action(1, agent(1), 10), 1));
+        store.add(example_record(create_action(2, agent(2), 10), 2));
+        mock_hdk.expect_agent_info().returning(|_| {
+            Ok(AgentInfo {
+                agent_initial_pubkey: agent(1),
+                agent_latest_pubkey: agent(1),
+                chain_head: (action_hash(0), 0, timestamp(0)),
+            })
+        });
+        let deletes = Arc::new(Mutex::new(Vec::new()));
+        let deleted = deletes.clone();
+        mock_hdk.expect_delete().returning(move |input| {
+            deleted
+                .lock()
+                .unwrap()
+                .push(input.deletes_action_hash.clone());
+            Ok(action_hash(100))
+        });
+        let signals = Arc::new(Mutex::new(Vec::new()));
+        let emitted = signals.clone();
+        mock_hdk.expect_emit_signal().returning(move |signal| {
+            emitted.lock().unwrap().push(signal);
+            Ok(())
+        });
+        set_hdk(mock_hdk);
+        (deletes, signals)
+    }
+
+    fn do_delete_batch(
+        action_hashes: Vec<ActionHashB64>,
+        ownership: Ownership,
+    ) -> ExternResult<Vec<ActionHashB64>> {
+        DoDelete {}.do_delete_batch::<Example, WasmError, ActionSignal<Example>>(
+            action_hashes,
+            String::from("example"),
+            None,
+            None,
+            ownership,
+            true,
+        )
+    }
+
+    #[test]
+    fn test_do_delete_batch() {
+        let (deletes, signals) = set_delete_hdk();
+        let action_hashes: Vec<ActionHashB64> = vec![action_hash(1).into(), action_hash(2).into()];
+        assert_eq!(
+            do_delete_batch(action_hashes.clone(), Ownership::Anyone),
+            Ok(action_hashes.clone())
+        );
+        assert_eq!(
+            deletes.lock().unwrap().clone(),
+            vec![action_hash(1), action_hash(2)]
+        );
+        // a single signal for the whole batch
+        let signal = ActionSignal::<Example> {
+            entry_type: String::from("example"),
+            action: ActionType::Delete,
+            data: SignalData::DeleteBatch(action_hashes),
+        };
+        assert_eq!(
+            signals.lock().unwrap().clone(),
+            vec![AppSignal::new(ExternIO::encode(signal).unwrap())]
+        );
+    }
+
+    #[test]
+    fn test_do_delete_batch_checks_ownership_of_every_entry_first() {
+        let (deletes, signals) = set_delete_hdk();
+        let result = do_delete_batch(
+            vec![action_hash(1).into(), action_hash(2).into()],
+            Ownership::AuthorOnly,
+        );
+        assert_eq!(
+            result,
+            Err(CrudError::NotOwner {
+                action_hash: action_hash(2).into(),
+                author: agent(2).into(),
+                caller: agent(1).into(),
+            }
+            .into())
+        );
+        // not even the entry which is owned by the caller is deleted
+        assert!(deletes.lock().unwrap().is_empty());
+        assert!(signals.lock().unwrap().is_empty());
+    }
 }
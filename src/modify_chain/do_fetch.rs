@@ -3,6 +3,8 @@ use crate::retrieval::fetch_entries::FetchEntries;
 #[cfg(not(feature = "mock"))]
 use crate::retrieval::fetch_links::FetchLinks;
 #[cfg(not(feature = "mock"))]
+use crate::retrieval::get_history_for_entry::GetHistory;
+#[cfg(not(feature = "mock"))]
 use crate::retrieval::get_latest_for_entry::GetLatestEntry;
 
 #[cfg(feature = "mock")]
@@ -10,9 +12,12 @@ use crate::retrieval::fetch_entries::MockFetchEntries as FetchEntries;
 #[cfg(feature = "mock")]
 use crate::retrieval::fetch_links::MockFetchLinks as FetchLinks;
 #[cfg(feature = "mock")]
+use crate::retrieval::get_history_for_entry::MockGetHistory as GetHistory;
+#[cfg(feature = "mock")]
 use crate::retrieval::get_latest_for_entry::MockGetLatestEntry as GetLatestEntry;
 
-use crate::wire_record::WireRecord;
+use crate::retrieval::outputs::{FetchPage, FetchReport};
+use crate::wire_record::{Revision, WireRecord};
 use hdi::hash_path::path::TypedPath;
 use hdk::prelude::*;
 
@@ -54,4 +59,79 @@ impl DoFetch {
         )?;
         Ok(entries)
     }
+
+    /// This is the exposed/public Zome function for either fetching ALL or a SPECIFIC list of the entries of the type,
+    /// along with a list of the entries which could not be fetched, and why.
+    pub fn do_fetch_report<T, E>(
+        &self,
+        fetch_entries: &FetchEntries,
+        fetch_links: &FetchLinks,
+        get_latest: &GetLatestEntry,
+        fetch_options: crate::retrieval::inputs::FetchOptions,
+        get_options: GetOptions,
+        link_type: LinkTypeFilter,
+        link_tag: Option<LinkTag>,
+        path: TypedPath,
+    ) -> ExternResult<FetchReport<T>>
+    where
+        Entry: TryFrom<T, Error = E>,
+        WasmError: From<E>,
+        T: 'static + Clone + TryFrom<SerializedBytes, Error = SerializedBytesError>,
+        E: 'static,
+    {
+        fetch_entries.fetch_entries_report::<T>(
+            fetch_links,
+            get_latest,
+            link_type,
+            link_tag,
+            path,
+            fetch_options,
+            get_options,
+        )
+    }
+
+    /// This is the exposed/public Zome function for fetching a single page of the entries of the type.
+    pub fn do_fetch_page<T, E>(
+        &self,
+        fetch_entries: &FetchEntries,
+        fetch_links: &FetchLinks,
+        get_latest: &GetLatestEntry,
+        page: crate::retrieval::inputs::FetchPageInput,
+        get_options: GetOptions,
+        link_type: LinkTypeFilter,
+        link_tag: Option<LinkTag>,
+        path: TypedPath,
+    ) -> ExternResult<FetchPage<T>>
+    where
+        Entry: TryFrom<T, Error = E>,
+        WasmError: From<E>,
+        T: 'static + Clone + TryFrom<SerializedBytes, Error = SerializedBytesError>,
+        E: 'static,
+    {
+        fetch_entries.fetch_entries_page::<T>(
+            fetch_links,
+            get_latest,
+            link_type,
+            link_tag,
+            path,
+            page,
+            get_options,
+        )
+    }
+
+    /// This is the exposed/public Zome function for fetching every version of a SPECIFIC entry of the type.
+    pub fn do_fetch_history<T, E>(
+        &self,
+        get_history: &GetHistory,
+        action_hash: ::holo_hash::ActionHashB64,
+        get_options: GetOptions,
+    ) -> ExternResult<Vec<Revision<T>>>
+    where
+        Entry: TryFrom<T, Error = E>,
+        WasmError: From<E>,
+        T: 'static + Clone + TryFrom<SerializedBytes, Error = SerializedBytesError>,
+        E: 'static,
+    {
+        get_history.get_history_for_entry::<T>(action_hash.into(), get_options)
+    }
 }
@@ -1,7 +1,12 @@
+use crate::datetime_queries::inputs::TimeIndex;
+use crate::errors::CrudError;
+use crate::modify_chain::ownership::{check_ownership, Ownership};
+use crate::modify_chain::utils::{add_time_path, get_signed_action, send_action_signal};
+use crate::retrieval::utils::{root_action, DEFAULT_MAX_UPDATE_DEPTH};
+use crate::signals::{RecordDiff, RecordHashes, SignalData, SignalPayload};
 use crate::wire_record::WireRecord;
-use crate::{datetime_queries::utils::serialize_err, modify_chain::utils::add_current_time_path};
 use hdk::prelude::*;
-use holo_hash::{ActionHashB64, AgentPubKey, EntryHashB64};
+use holo_hash::{ActionHashB64, AgentPubKey, AgentPubKeyB64, EntryHashB64};
 
 #[cfg(feature = "mock")]
 use ::mockall::automock;
@@ -13,8 +18,14 @@ pub struct DoUpdate {}
 #[cfg_attr(feature = "mock", automock)]
 impl DoUpdate {
     /// This will add an update to an entry.
-    /// It can also optionally send a signal of this event to all peers supplied in `send_signal_to_peers`
+    /// It can also optionally send a signal of this event to all peers supplied in `send_signal_to_peers`,
+    /// or in `send_signal_with_secrets` along with the cap secret of each of them,
+    /// and, if `emit_locally`, emit it to the UIs of this agent as well
     /// uses `ChainTopOrdering::Relaxed` such that multiple updates can be committed in parallel
+    /// The calling agent has to be allowed to update the entry by `ownership`
+    /// The updated entry is also linked off of the current time path of each of the `time_indexes`
+    /// How much of the updated record the signal carries is decided by the `signal_payload`
+    /// The record being updated is read with the `get_options`
     pub fn do_update<T, E, S, R>(
         &self,
         entry: T,
@@ -22,66 +33,379 @@ impl DoUpdate {
         entry_type_id: String,
         scoped_link_type: R,
         send_signal_to_peers: Option<Vec<AgentPubKey>>,
-        add_time_path: Option<String>,
+        send_signal_with_secrets: Option<Vec<(AgentPubKey, CapSecret)>>,
+        time_indexes: Vec<TimeIndex>,
+        ownership: Ownership,
+        signal_payload: SignalPayload,
+        emit_locally: bool,
+        get_options: GetOptions,
     ) -> ExternResult<WireRecord<T>>
     where
         Entry: TryFrom<T, Error = E>,
         ScopedLinkType: TryFrom<R, Error = E>,
         R: Clone,
         WasmError: From<E>,
-        T: 'static + Clone,
+        T: 'static
+            + Clone
+            + serde::Serialize
+            + TryFrom<SerializedBytes, Error = SerializedBytesError>,
         AppEntryBytes: TryFrom<T, Error = E>,
         S: 'static + From<crate::signals::ActionSignal<T>> + serde::Serialize + std::fmt::Debug,
         E: 'static,
     {
-        // calling update instead of update_entry to be able to indicate relaxed chain ordering
-        hdk::entry::update(UpdateInput {
-            original_action_address: action_hash.clone().into(),
-            entry: Entry::App(entry.clone().try_into()?),
-            chain_top_ordering: ChainTopOrdering::Relaxed,
-        })?;
-        let entry_address = hash_entry(entry.clone())?;
-        match add_time_path {
-            None => (),
-            Some(base_component) => {
-                // create a time_path
-                add_current_time_path(
-                    base_component,
-                    entry_address.clone(),
-                    scoped_link_type,
-                    LinkTag::from(vec![]),
-                )?;
-            }
-        }
-        let updated_at = sys_time()?;
-        // get create time from the action_hash
-        let maybe_record = get(ActionHash::from(action_hash.clone()), GetOptions::default())?;
-        let created_at = match maybe_record {
-            Some(record) => Ok(record.signed_action().action().timestamp()),
-            None => Err(wasm_error!(WasmErrorInner::Guest(String::from(
-                "unable to get record from provided action hash",
-            )))),
-        }?;
-        let wire_entry: WireRecord<T> = WireRecord {
+        check_ownership(&ownership, &action_hash)?;
+        let will_signal =
+            send_signal_to_peers.is_some() || send_signal_with_secrets.is_some() || emit_locally;
+        let previous = previous_entry_for_diff::<T>(
+            &action_hash,
+            get_options.clone(),
+            signal_payload,
+            will_signal,
+        )?;
+        let wire_entry = update_entry::<T, E, R>(
             entry,
             action_hash,
-            entry_hash: EntryHashB64::new(entry_address),
-            created_at,
-            updated_at,
-        };
-        match send_signal_to_peers {
-            None => (),
-            Some(vec_peers) => {
-                let action_signal: crate::signals::ActionSignal<T> = crate::signals::ActionSignal {
-                    entry_type: entry_type_id,
-                    action: crate::signals::ActionType::Update,
-                    data: crate::signals::SignalData::Update(wire_entry.clone()),
-                };
-                let signal = S::from(action_signal);
-                let payload = ExternIO::encode(signal).map_err(serialize_err)?;
-                send_remote_signal(payload, vec_peers)?;
-            }
+            scoped_link_type,
+            time_indexes,
+            get_options,
+        )?;
+        if will_signal {
+            let action_signal: crate::signals::ActionSignal<T> = crate::signals::ActionSignal {
+                entry_type: entry_type_id,
+                action: crate::signals::ActionType::Update,
+                data: updated_signal_data(wire_entry.clone(), previous, signal_payload)?,
+            };
+            send_action_signal::<T, S>(
+                action_signal,
+                send_signal_to_peers,
+                send_signal_with_secrets,
+                emit_locally,
+            )?;
         }
         Ok(wire_entry)
     }
+
+    /// This will add many updates at once, each just like [do_update](DoUpdate::do_update) would,
+    /// but all in a single zome call. Instead of one signal per update, it will optionally send a
+    /// single `SignalData::UpdateBatch` signal to all peers supplied in `send_signal_to_peers`
+    /// or `send_signal_with_secrets`,
+    /// and emit it locally if `emit_locally`.
+    /// Nothing is updated unless the calling agent is allowed to update every one of the entries by `ownership`
+    pub fn do_update_batch<T, E, S, R>(
+        &self,
+        updates: Vec<(T, ActionHashB64)>,
+        entry_type_id: String,
+        scoped_link_type: R,
+        send_signal_to_peers: Option<Vec<AgentPubKey>>,
+        send_signal_with_secrets: Option<Vec<(AgentPubKey, CapSecret)>>,
+        time_indexes: Vec<TimeIndex>,
+        ownership: Ownership,
+        signal_payload: SignalPayload,
+        emit_locally: bool,
+        get_options: GetOptions,
+    ) -> ExternResult<Vec<WireRecord<T>>>
+    where
+        Entry: TryFrom<T, Error = E>,
+        ScopedLinkType: TryFrom<R, Error = E>,
+        R: Clone,
+        WasmError: From<E>,
+        T: 'static
+            + Clone
+            + serde::Serialize
+            + TryFrom<SerializedBytes, Error = SerializedBytesError>,
+        AppEntryBytes: TryFrom<T, Error = E>,
+        S: 'static + From<crate::signals::ActionSignal<T>> + serde::Serialize + std::fmt::Debug,
+        E: 'static,
+    {
+        for (_, action_hash) in updates.iter() {
+            check_ownership(&ownership, action_hash)?;
+        }
+        let will_signal =
+            send_signal_to_peers.is_some() || send_signal_with_secrets.is_some() || emit_locally;
+        let mut wire_entries = Vec::new();
+        let mut previous_entries = Vec::new();
+        for (entry, action_hash) in updates {
+            previous_entries.push(previous_entry_for_diff::<T>(
+                &action_hash,
+                get_options.clone(),
+                signal_payload,
+                will_signal,
+            )?);
+            wire_entries.push(update_entry::<T, E, R>(
+                entry,
+                action_hash,
+                scoped_link_type.clone(),
+                time_indexes.clone(),
+                get_options.clone(),
+            )?);
+        }
+        if will_signal {
+            let data = match signal_payload {
+                SignalPayload::Full => SignalData::UpdateBatch(wire_entries.clone()),
+                SignalPayload::HashesOnly => {
+                    SignalData::NotifyBatch(wire_entries.iter().map(RecordHashes::from).collect())
+                }
+                SignalPayload::Diff => SignalData::UpdateDiffBatch(
+                    wire_entries
+                        .iter()
+                        .zip(previous_entries)
+                        .filter_map(|(wire_entry, previous)| {
+                            previous.map(|previous| RecordDiff::new(&previous, wire_entry))
+                        })
+                        .collect::<ExternResult<Vec<RecordDiff>>>()?,
+                ),
+            };
+            let action_signal: crate::signals::ActionSignal<T> = crate::signals::ActionSignal {
+                entry_type: entry_type_id,
+                action: crate::signals::ActionType::Update,
+                data,
+            };
+            send_action_signal::<T, S>(
+                action_signal,
+                send_signal_to_peers,
+                send_signal_with_secrets,
+                emit_locally,
+            )?;
+        }
+        Ok(wire_entries)
+    }
+}
+
+/// the entry of the record at `action_hash`, which is the version that the client is updating,
+/// and so the one to diff the update against, when a signal with a [SignalPayload::Diff] will be sent
+fn previous_entry_for_diff<T>(
+    action_hash: &ActionHashB64,
+    get_options: GetOptions,
+    signal_payload: SignalPayload,
+    will_signal: bool,
+) -> ExternResult<Option<T>>
+where
+    T: 'static + TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+    if !will_signal || signal_payload != SignalPayload::Diff {
+        return Ok(None);
+    }
+    let action_hash = ActionHash::from(action_hash.clone());
+    let record = match get(action_hash.clone(), get_options)? {
+        Some(record) => record,
+        None => return Err(CrudError::NotFound(AnyLinkableHash::from(action_hash).into()).into()),
+    };
+    match record.entry().to_app_option::<T>() {
+        Ok(Some(entry)) => Ok(Some(entry)),
+        Ok(None) => Err(CrudError::NotAnEntry(AnyLinkableHash::from(action_hash).into()).into()),
+        Err(_) => Err(CrudError::WrongEntryType(AnyLinkableHash::from(action_hash).into()).into()),
+    }
+}
+
+/// the data of a signal of the `wire_entry` having been updated, as the `signal_payload` asks for
+fn updated_signal_data<T: serde::Serialize>(
+    wire_entry: WireRecord<T>,
+    previous: Option<T>,
+    signal_payload: SignalPayload,
+) -> ExternResult<SignalData<T>> {
+    Ok(match (signal_payload, previous) {
+        (SignalPayload::HashesOnly, _) => SignalData::Notify(RecordHashes::from(&wire_entry)),
+        (SignalPayload::Diff, Some(previous)) => {
+            SignalData::UpdateDiff(RecordDiff::new(&previous, &wire_entry)?)
+        }
+        _ => SignalData::Update(wire_entry),
+    })
+}
+
+/// add the update, and the links to it, that [do_update](DoUpdate::do_update) and
+/// [do_update_batch](DoUpdate::do_update_batch) are responsible for, without signaling
+fn update_entry<T, E, R>(
+    entry: T,
+    action_hash: ActionHashB64,
+    scoped_link_type: R,
+    time_indexes: Vec<TimeIndex>,
+    get_options: GetOptions,
+) -> ExternResult<WireRecord<T>>
+where
+    Entry: TryFrom<T, Error = E>,
+    ScopedLinkType: TryFrom<R, Error = E>,
+    R: Clone,
+    WasmError: From<E>,
+    T: Clone,
+    AppEntryBytes: TryFrom<T, Error = E>,
+{
+    // calling update instead of update_entry to be able to indicate relaxed chain ordering
+    let update_address = hdk::entry::update(UpdateInput {
+        original_action_address: action_hash.clone().into(),
+        entry: Entry::App(entry.clone().try_into()?),
+        chain_top_ordering: ChainTopOrdering::Relaxed,
+    })?;
+    let entry_address = hash_entry(entry.clone())?;
+    // read the actions back so that the timestamps match the ones stored in the records exactly,
+    // and so that an update of an update still reports the original, just like a fetch would
+    let update_action = get_signed_action(update_address.clone(), GetOptions::local())?;
+    for time_index in time_indexes {
+        // create a time_path, which resolves back to the original through the update
+        add_time_path(
+            time_index,
+            entry_address.clone(),
+            scoped_link_type.clone(),
+            LinkTag::from(vec![]),
+            update_action.action().timestamp(),
+        )?;
+    }
+    let original_action = root_action(
+        get_signed_action(ActionHash::from(action_hash), get_options.clone())?,
+        get_options,
+        DEFAULT_MAX_UPDATE_DEPTH,
+    )?;
+    let wire_entry: WireRecord<T> = WireRecord {
+        entry,
+        action_hash: ActionHashB64::new(original_action.as_hash().to_owned()),
+        entry_hash: EntryHashB64::new(entry_address),
+        created_at: original_action.action().timestamp(),
+        updated_at: update_action.action().timestamp(),
+        created_by: Some(AgentPubKeyB64::new(
+            original_action.action().author().to_owned(),
+        )),
+        updated_by: Some(AgentPubKeyB64::new(
+            update_action.action().author().to_owned(),
+        )),
+        latest_action_hash: Some(ActionHashB64::new(update_address)),
+    };
+    Ok(wire_entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DoUpdate;
+    use crate::crud::example::Example;
+    use crate::modify_chain::ownership::Ownership;
+    use crate::signals::{
+        ActionSignal, ActionType, RecordDiff, RecordHashes, SignalData, SignalPayload,
+    };
+    use crate::test_helpers::*;
+    use hdk::prelude::*;
+    use std::sync::{Arc, Mutex};
+
+    /// set a mocked hdk which updates the records in the `store`,
+    /// and keeps the signals emitted locally in the returned list
+    fn set_update_hdk(store: &RecordStore) -> Arc<Mutex<Vec<AppSignal>>> {
+        let mut mock_hdk = MockHdkT::new();
+        store.install(&mut mock_hdk);
+        mock_hdk.expect_hash().returning(fake_hash);
+        let updates = store.clone();
+        let mut next = 10;
+        mock_hdk.expect_update().returning(move |input| {
+            next += 1;
+            let original = updates
+                .get(&input.original_action_address.clone().into())
+                .unwrap();
+            let action = update_action(next, original.signed_action(), agent(1), next as i64);
+            updates.add(Record::new(action, Some(input.entry)));
+            Ok(action_hash(next))
+        });
+        let signals = Arc::new(Mutex::new(Vec::new()));
+        let emitted = signals.clone();
+        mock_hdk.expect_emit_signal().returning(move |signal| {
+            emitted.lock().unwrap().push(signal);
+            Ok(())
+        });
+        set_hdk(mock_hdk);
+        signals
+    }
+
+    /// an entry numbered 1, which has already been updated to one numbered 2
+    fn updated_store() -> RecordStore {
+        let store = RecordStore::default();
+        let create = create_action(1, agent(1), 1);
+        store.add(example_record(create.clone(), 1));
+        store.add(example_record(update_action(2, &create, agent(1), 2), 2));
+        store
+    }
+
+    fn update_signal(data: SignalData<Example>) -> AppSignal {
+        AppSignal::new(
+            ExternIO::encode(ActionSignal {
+                entry_type: String::from("example"),
+                action: ActionType::Update,
+                data,
+            })
+            .unwrap(),
+        )
+    }
+
+    fn do_update(
+        number: i32,
+        action_hash: ActionHash,
+        signal_payload: SignalPayload,
+    ) -> ExternResult<crate::wire_record::WireRecord<Example>> {
+        DoUpdate {}.do_update::<Example, WasmError, ActionSignal<Example>, TestLinkType>(
+            Example { number },
+            action_hash.into(),
+            String::from("example"),
+            TestLinkType,
+            None,
+            None,
+            vec![],
+            Ownership::Anyone,
+            signal_payload,
+            true,
+            GetOptions::local(),
+        )
+    }
+
+    #[test]
+    fn test_do_update_diff() {
+        let signals = set_update_hdk(&updated_store());
+        let wire_entry = do_update(3, action_hash(1), SignalPayload::Diff).unwrap();
+        // diffed against the version which was updated, not the latest one
+        let diff = RecordDiff::new(&Example { number: 1 }, &wire_entry).unwrap();
+        assert_eq!(
+            signals.lock().unwrap().clone(),
+            vec![update_signal(SignalData::UpdateDiff(diff))]
+        );
+    }
+
+    #[test]
+    fn test_do_update_notify() {
+        let signals = set_update_hdk(&updated_store());
+        let wire_entry = do_update(3, action_hash(2), SignalPayload::HashesOnly).unwrap();
+        // an update of an update still reports the original
+        assert_eq!(wire_entry.action_hash, action_hash(1).into());
+        assert_eq!(wire_entry.latest_action_hash, Some(action_hash(11).into()));
+        assert_eq!(
+            signals.lock().unwrap().clone(),
+            vec![update_signal(SignalData::Notify(RecordHashes::from(
+                &wire_entry
+            )))]
+        );
+    }
+
+    #[test]
+    fn test_do_update_batch_diff() {
+        let signals = set_update_hdk(&updated_store());
+        let wire_entries = DoUpdate {}
+            .do_update_batch::<Example, WasmError, ActionSignal<Example>, TestLinkType>(
+                vec![
+                    (Example { number: 3 }, action_hash(1).into()),
+                    (Example { number: 4 }, action_hash(2).into()),
+                ],
+                String::from("example"),
+                TestLinkType,
+                None,
+                None,
+                vec![],
+                Ownership::Anyone,
+                SignalPayload::Diff,
+                true,
+                GetOptions::local(),
+            )
+            .unwrap();
+        assert_eq!(wire_entries.len(), 2);
+        // each update is diffed against the version it updated
+        let diffs = vec![
+            RecordDiff::new(&Example { number: 1 }, &wire_entries[0]).unwrap(),
+            RecordDiff::new(&Example { number: 2 }, &wire_entries[1]).unwrap(),
+        ];
+        assert_eq!(
+            signals.lock().unwrap().clone(),
+            vec![update_signal(SignalData::UpdateDiffBatch(diffs))]
+        );
+    }
 }
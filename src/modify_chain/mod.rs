@@ -2,4 +2,5 @@ pub mod do_create;
 pub mod do_delete;
 pub mod do_fetch;
 pub mod do_update;
+pub mod ownership;
 pub mod utils;
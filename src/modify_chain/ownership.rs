@@ -0,0 +1,107 @@
+use crate::errors::CrudError;
+use crate::modify_chain::utils::get_signed_action;
+use crate::retrieval::utils::{root_action, DEFAULT_MAX_UPDATE_DEPTH};
+use hdk::prelude::*;
+use holo_hash::{ActionHashB64, AgentPubKey, AgentPubKeyB64};
+
+/// Who is allowed to update or delete an entry through [DoUpdate](crate::modify_chain::do_update::DoUpdate)
+/// and [DoDelete](crate::modify_chain::do_delete::DoDelete)
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Ownership {
+    /// any agent can update or delete any entry
+    #[default]
+    Anyone,
+    /// only the author of the original Create can update or delete the entry
+    AuthorOnly,
+    /// the author of the original Create can update or delete the entry, as well as any
+    /// agent for which the function, given the `action_hash` of the original Create,
+    /// its author, and the calling agent, returns true. Useful for shared editing.
+    AuthorOr(fn(&ActionHash, &AgentPubKey, &AgentPubKey) -> ExternResult<bool>),
+}
+
+/// check that the calling agent is allowed by `ownership` to modify the entry at `action_hash`,
+/// which can be the original Create, or any update of it.
+/// The calling agent is identified by its latest public key, which is the one it authors with.
+/// Returns [CrudError::NotOwner] if not.
+pub fn check_ownership(ownership: &Ownership, action_hash: &ActionHashB64) -> ExternResult<()> {
+    let allow_list = match ownership {
+        Ownership::Anyone => return Ok(()),
+        Ownership::AuthorOnly => None,
+        Ownership::AuthorOr(allow_list) => Some(allow_list),
+    };
+    let original_action = root_action(
+        get_signed_action(ActionHash::from(action_hash.clone()), GetOptions::default())?,
+        GetOptions::default(),
+        DEFAULT_MAX_UPDATE_DEPTH,
+    )?;
+    let author = original_action.action().author();
+    let caller = agent_info()?.agent_latest_pubkey;
+    if author == &caller {
+        return Ok(());
+    }
+    if let Some(allow_list) = allow_list {
+        if allow_list(original_action.as_hash(), author, &caller)? {
+            return Ok(());
+        }
+    }
+    Err(CrudError::NotOwner {
+        action_hash: action_hash.clone(),
+        author: AgentPubKeyB64::new(author.to_owned()),
+        caller: AgentPubKeyB64::new(caller),
+    }
+    .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_ownership, Ownership};
+    use crate::errors::CrudError;
+    use crate::test_helpers::*;
+    use hdk::prelude::*;
+
+    /// a mocked hdk where the entry at `action_hash(1)` was created by `agent(1)`,
+    /// and updated at `action_hash(2)` by `agent(2)`, which is called by `caller`
+    fn set_caller(caller: AgentPubKey) {
+        let mut mock_hdk = MockHdkT::new();
+        let store = RecordStore::default();
+        store.install(&mut mock_hdk);
+        let create = create_action(1, agent(1), 10);
+        store.add(example_record(update_action(2, &create, agent(2), 20), 2));
+        store.add(example_record(create, 1));
+        mock_hdk.expect_agent_info().returning(move |_| {
+            Ok(AgentInfo {
+                // the key the agent started out with, before it was rotated
+                agent_initial_pubkey: agent(9),
+                agent_latest_pubkey: caller.clone(),
+                chain_head: (action_hash(0), 0, timestamp(0)),
+            })
+        });
+        set_hdk(mock_hdk);
+    }
+
+    #[test]
+    fn test_check_ownership() {
+        let update_hash = action_hash(2).into();
+        // the original author, not the author of the update, owns the entry
+        set_caller(agent(1));
+        assert_eq!(
+            check_ownership(&Ownership::AuthorOnly, &update_hash),
+            Ok(())
+        );
+        set_caller(agent(2));
+        assert_eq!(
+            check_ownership(&Ownership::AuthorOnly, &update_hash),
+            Err(CrudError::NotOwner {
+                action_hash: update_hash.clone(),
+                author: agent(1).into(),
+                caller: agent(2).into(),
+            }
+            .into())
+        );
+        let editors = Ownership::AuthorOr(|_, _, caller| Ok(caller == &agent(2)));
+        assert_eq!(check_ownership(&editors, &update_hash), Ok(()));
+        set_caller(agent(3));
+        assert!(check_ownership(&editors, &update_hash).is_err());
+        assert_eq!(check_ownership(&Ownership::Anyone, &update_hash), Ok(()));
+    }
+}
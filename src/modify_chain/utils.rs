@@ -1,4 +1,8 @@
-use chrono::{DateTime, Datelike, NaiveDateTime, Timelike, Utc};
+use crate::datetime_queries::inputs::TimeIndex;
+use crate::datetime_queries::utils::{date_time_from_timestamp, serialize_err};
+use crate::errors::CrudError;
+use crate::signals::{send_signal_with_secret, ActionSignal};
+use chrono::{DateTime, Utc};
 use hdk::prelude::*;
 use holo_hash::EntryHash;
 
@@ -29,35 +33,75 @@ where
     })
 }
 
+/// wrap the `action_signal` in the zome's own signal type `S`, and send it to all of the `peers`, if given,
+/// and to all of the `peers_with_secrets`, if given, along with the cap secret of each of them,
+/// as they need to signal an agent who only grants access to `recv_remote_signal` with a secret.
+/// When `emit_locally`, the same signal is also emitted to the UIs of this agent, without
+/// a round trip through `recv_remote_signal`, whether or not it is sent to any peers.
+/// Like `send_remote_signal` does, it gives up on the peers which can't be signalled
+pub fn send_action_signal<T, S>(
+    action_signal: ActionSignal<T>,
+    peers: Option<Vec<AgentPubKey>>,
+    peers_with_secrets: Option<Vec<(AgentPubKey, CapSecret)>>,
+    emit_locally: bool,
+) -> ExternResult<()>
+where
+    S: From<ActionSignal<T>> + serde::Serialize + std::fmt::Debug,
+{
+    let signal = S::from(action_signal);
+    if emit_locally {
+        emit_signal(&signal)?;
+    }
+    if let Some(peers_with_secrets) = peers_with_secrets {
+        send_signal_with_secret(&signal, peers_with_secrets)?;
+    }
+    if let Some(peers) = peers {
+        let payload = ExternIO::encode(signal).map_err(serialize_err)?;
+        send_remote_signal(payload, peers)?;
+    }
+    Ok(())
+}
+
+/// get the action at `action_hash`, such as one committed earlier in this zome call,
+/// so that the timestamp and author returned to the client are exactly those stored in the record
+pub fn get_signed_action(
+    action_hash: ActionHash,
+    get_options: GetOptions,
+) -> ExternResult<SignedActionHashed> {
+    match get(action_hash.clone(), get_options)? {
+        Some(record) => Ok(record.signed_action().to_owned()),
+        None => Err(CrudError::NotFound(AnyLinkableHash::from(action_hash).into()).into()),
+    }
+}
+
 /// get the current UTC date time
 pub fn now_date_time() -> ExternResult<::chrono::DateTime<::chrono::Utc>> {
-    let time = sys_time()?.as_seconds_and_nanos();
-
-    let date: DateTime<Utc> =
-        DateTime::from_utc(NaiveDateTime::from_timestamp(time.0, time.1), Utc);
-    Ok(date)
+    date_time_from_timestamp(sys_time()?)
 }
 
-pub fn add_current_time_path<T, E>(
-    base_component: String,
+/// link the entry at `entry_address` off of the path of the `timestamp` in the `time_index`,
+/// at the granularity of the `time_index`. Pass the timestamp of the action which created or updated
+/// the entry, so that it is indexed under the same time that fetches filter it by.
+/// Time indexes are always in UTC; queries in local time are converted to UTC when fetching
+pub fn add_time_path<T, E>(
+    time_index: TimeIndex,
     entry_address: EntryHash,
     link_type: T,
     link_tag: LinkTag,
+    timestamp: Timestamp,
 ) -> ExternResult<()>
 where
     ScopedLinkType: TryFrom<T, Error = E>,
     T: Clone,
     WasmError: From<E>,
 {
-    let date: DateTime<Utc> = now_date_time()?;
+    let date: DateTime<Utc> = date_time_from_timestamp(timestamp)?;
 
-    let time_path = crate::datetime_queries::utils::hour_path_from_date(
+    let time_path = crate::datetime_queries::utils::time_path_from_date(
         link_type.clone(),
-        base_component,
-        date.year(),
-        date.month(),
-        date.day(),
-        date.hour(),
+        time_index.base_component,
+        date,
+        time_index.granularity,
     )?;
 
     time_path.ensure()?;
@@ -69,3 +113,44 @@ where
     )?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::add_time_path;
+    use crate::datetime_queries::inputs::{TimeIndex, TimeIndexGranularity};
+    use crate::datetime_queries::utils::{date_time_from_timestamp, time_path_from_date};
+    use crate::test_helpers::{entry_hash, set_link_store, timestamp, TestLinkType};
+    use hdk::prelude::*;
+
+    #[test]
+    fn test_add_time_path_at_timestamp() {
+        // sys_time is not mocked, so the time has to come from the timestamp
+        let store = set_link_store();
+        let time_index = TimeIndex {
+            base_component: String::from("todo_time"),
+            granularity: TimeIndexGranularity::Minute,
+        };
+        // 2021-01-01T10:20:30Z
+        let at = timestamp(1_609_496_430_000_000);
+        add_time_path(
+            time_index.clone(),
+            entry_hash(1),
+            TestLinkType,
+            LinkTag::from(vec![]),
+            at,
+        )
+        .unwrap();
+        let path = time_path_from_date(
+            TestLinkType,
+            time_index.base_component,
+            date_time_from_timestamp(at).unwrap(),
+            time_index.granularity,
+        )
+        .unwrap();
+        assert_eq!(path.path, Path::from("todo_time.2021.01.01.10.20"));
+        assert_eq!(
+            store.targets(&path.path_entry_hash().unwrap().into()),
+            vec![entry_hash(1).into()]
+        );
+    }
+}
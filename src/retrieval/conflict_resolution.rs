@@ -0,0 +1,160 @@
+use hdk::prelude::*;
+use std::fmt;
+use std::rc::Rc;
+
+/// Decides which of the concurrent updates of an entry is its "latest" version.
+/// Implementations must only depend on the updates themselves (and not, for example, on
+/// the order they are passed in) so that every peer converges on the same "latest" value.
+pub trait ConflictResolver {
+    fn resolve(&self, updates: Vec<SignedActionHashed>) -> Option<SignedActionHashed>;
+}
+
+impl<F> ConflictResolver for F
+where
+    F: Fn(Vec<SignedActionHashed>) -> Option<SignedActionHashed>,
+{
+    fn resolve(&self, updates: Vec<SignedActionHashed>) -> Option<SignedActionHashed> {
+        self(updates)
+    }
+}
+
+/// The built-in strategies for resolving concurrent updates, plus
+/// an escape hatch for anything implementing [ConflictResolver]
+#[derive(Clone, Default)]
+pub enum ConflictStrategy {
+    /// the update with the latest timestamp wins,
+    /// with ties broken by the highest ActionHash
+    #[default]
+    LastWriterWins,
+    /// the update with the earliest timestamp wins,
+    /// with ties broken by the lowest ActionHash
+    FirstWriterWins,
+    /// updates by agents earlier in the list win over updates by agents later in the list,
+    /// who win over any agent not in the list. Updates by equally ranked agents are
+    /// resolved using [LastWriterWins](ConflictStrategy::LastWriterWins)
+    AuthorPriority(Vec<AgentPubKey>),
+    /// use a custom resolver, such as a closure
+    Custom(Rc<dyn ConflictResolver>),
+}
+
+impl ConflictStrategy {
+    /// wrap a custom resolver, such as a closure, in a [ConflictStrategy]
+    pub fn custom(resolver: impl ConflictResolver + 'static) -> Self {
+        Self::Custom(Rc::new(resolver))
+    }
+}
+
+impl ConflictResolver for ConflictStrategy {
+    fn resolve(&self, updates: Vec<SignedActionHashed>) -> Option<SignedActionHashed> {
+        match self {
+            Self::LastWriterWins => updates.into_iter().max_by(|a, b| {
+                (a.action().timestamp(), a.as_hash()).cmp(&(b.action().timestamp(), b.as_hash()))
+            }),
+            Self::FirstWriterWins => updates.into_iter().min_by(|a, b| {
+                (a.action().timestamp(), a.as_hash()).cmp(&(b.action().timestamp(), b.as_hash()))
+            }),
+            Self::AuthorPriority(authors) => {
+                let rank = |update: &SignedActionHashed| {
+                    authors
+                        .iter()
+                        .position(|author| author == update.action().author())
+                        .unwrap_or(authors.len())
+                };
+                let best_rank = updates.iter().map(rank).min()?;
+                Self::LastWriterWins.resolve(
+                    updates
+                        .into_iter()
+                        .filter(|update| rank(update) == best_rank)
+                        .collect(),
+                )
+            }
+            Self::Custom(resolver) => resolver.resolve(updates),
+        }
+    }
+}
+
+impl fmt::Debug for ConflictStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::LastWriterWins => write!(f, "LastWriterWins"),
+            Self::FirstWriterWins => write!(f, "FirstWriterWins"),
+            Self::AuthorPriority(authors) => {
+                f.debug_tuple("AuthorPriority").field(authors).finish()
+            }
+            Self::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+impl PartialEq for ConflictStrategy {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::LastWriterWins, Self::LastWriterWins) => true,
+            (Self::FirstWriterWins, Self::FirstWriterWins) => true,
+            (Self::AuthorPriority(a), Self::AuthorPriority(b)) => a == b,
+            (Self::Custom(a), Self::Custom(b)) => Rc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConflictResolver, ConflictStrategy};
+    use crate::test_helpers::*;
+    use hdk::prelude::*;
+
+    /// updates of the same original, made by `agent(author)` at `micros`, at `action_hash(n)`
+    fn update(n: u8, author: u8, micros: i64) -> SignedActionHashed {
+        update_action(n, &create_action(1, agent(1), 0), agent(author), micros)
+    }
+
+    fn resolved(strategy: ConflictStrategy, updates: Vec<SignedActionHashed>) -> Option<u8> {
+        // every peer has to converge on the same update, whatever order it got them in
+        let mut reversed = updates.clone();
+        reversed.reverse();
+        let latest = strategy.resolve(updates);
+        assert_eq!(latest, strategy.resolve(reversed));
+        latest.map(|update| update.as_hash().get_raw_36()[0])
+    }
+
+    #[test]
+    fn test_last_writer_wins() {
+        let updates = vec![update(2, 1, 10), update(3, 1, 30), update(4, 1, 30)];
+        // the tie between 3 and 4 is broken by the highest hash
+        assert_eq!(resolved(ConflictStrategy::LastWriterWins, updates), Some(4));
+        assert_eq!(resolved(ConflictStrategy::LastWriterWins, vec![]), None);
+    }
+
+    #[test]
+    fn test_first_writer_wins() {
+        let updates = vec![update(2, 1, 30), update(3, 1, 10), update(4, 1, 10)];
+        // the tie between 3 and 4 is broken by the lowest hash
+        assert_eq!(
+            resolved(ConflictStrategy::FirstWriterWins, updates),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn test_author_priority() {
+        let updates = vec![update(2, 1, 30), update(3, 2, 10), update(4, 2, 20)];
+        let strategy = ConflictStrategy::AuthorPriority(vec![agent(2), agent(1)]);
+        // the latest of the updates by the highest ranked author
+        assert_eq!(resolved(strategy, updates.clone()), Some(4));
+        // agents who aren't in the list rank below everyone who is
+        let strategy = ConflictStrategy::AuthorPriority(vec![agent(1)]);
+        assert_eq!(resolved(strategy, updates), Some(2));
+    }
+
+    #[test]
+    fn test_custom() {
+        let updates = vec![update(2, 1, 30), update(3, 1, 10)];
+        let strategy = ConflictStrategy::custom(|updates: Vec<SignedActionHashed>| {
+            updates
+                .into_iter()
+                .min_by_key(|update| update.as_hash().to_owned())
+        });
+        assert_eq!(resolved(strategy, updates), Some(2));
+    }
+}
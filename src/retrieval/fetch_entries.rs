@@ -11,10 +11,11 @@ use crate::retrieval::get_latest_for_entry::MockGetLatestEntry as GetLatestEntry
 #[cfg(feature = "mock")]
 use ::mockall::automock;
 
-use crate::retrieval::inputs::FetchOptions;
+use crate::retrieval::inputs::{FetchOptions, FetchPageInput};
+use crate::retrieval::outputs::{FetchPage, FetchReport};
+use crate::retrieval::utils::get_latest_for_targets;
 use crate::wire_record::WireRecord;
 use hdk::prelude::*;
-use std::convert::identity;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct FetchEntries {}
@@ -47,22 +48,76 @@ impl FetchEntries {
                 // TODO: will have to instantiate or pass in the struct
             }
             FetchOptions::Specific(vec_entry_hash) => {
-                let entries = vec_entry_hash
-                    .iter()
-                    .map(|entry_hash| {
-                        get_latest.get_latest_for_entry::<EntryType>(
-                            EntryHash::from(entry_hash.clone()).into(),
-                            get_options.clone(),
-                        )
-                    })
-                    // drop Err(_) and unwraps Ok(_)
-                    .filter_map(Result::ok)
-                    // drop None and unwraps Some(_)
-                    .filter_map(identity)
-                    .map(|x| WireRecord::from(x))
+                let targets = vec_entry_hash
+                    .into_iter()
+                    .map(|entry_hash| EntryHash::from(entry_hash).into())
                     .collect();
-                Ok(entries)
+                Ok(get_latest_for_targets::<EntryType>(get_latest, targets, get_options).records)
             }
         }
     }
+
+    /// Like [fetch_entries](FetchEntries::fetch_entries) but also reports which entries
+    /// could not be fetched, and why.
+    pub fn fetch_entries_report<
+        EntryType: 'static + TryFrom<SerializedBytes, Error = SerializedBytesError>,
+    >(
+        &self,
+        fetch_links: &FetchLinks,
+        get_latest: &GetLatestEntry,
+        link_type: LinkTypeFilter,
+        link_tag: Option<LinkTag>,
+        entry_path: TypedPath,
+        fetch_options: FetchOptions,
+        get_options: GetOptions,
+    ) -> Result<FetchReport<EntryType>, WasmError> {
+        match fetch_options {
+            FetchOptions::All => {
+                let path_hash = entry_path.path_entry_hash()?;
+                fetch_links.fetch_links_report::<EntryType>(
+                    get_latest,
+                    path_hash,
+                    link_type,
+                    link_tag,
+                    get_options,
+                )
+            }
+            FetchOptions::Specific(vec_entry_hash) => {
+                let targets = vec_entry_hash
+                    .into_iter()
+                    .map(|entry_hash| EntryHash::from(entry_hash).into())
+                    .collect();
+                Ok(get_latest_for_targets::<EntryType>(
+                    get_latest,
+                    targets,
+                    get_options,
+                ))
+            }
+        }
+    }
+
+    /// Fetch a single page of the entries of a certain type which are linked to a path.
+    /// See [fetch_links_page](crate::retrieval::fetch_links::FetchLinks::fetch_links_page) for how pages are ordered.
+    pub fn fetch_entries_page<
+        EntryType: 'static + TryFrom<SerializedBytes, Error = SerializedBytesError>,
+    >(
+        &self,
+        fetch_links: &FetchLinks,
+        get_latest: &GetLatestEntry,
+        link_type: LinkTypeFilter,
+        link_tag: Option<LinkTag>,
+        entry_path: TypedPath,
+        page: FetchPageInput,
+        get_options: GetOptions,
+    ) -> Result<FetchPage<EntryType>, WasmError> {
+        let path_hash = entry_path.path_entry_hash()?;
+        fetch_links.fetch_links_page::<EntryType>(
+            get_latest,
+            path_hash,
+            link_type,
+            link_tag,
+            page,
+            get_options,
+        )
+    }
 }
@@ -3,9 +3,12 @@ use crate::retrieval::get_latest_for_entry::GetLatestEntry;
 #[cfg(feature = "mock")]
 use crate::retrieval::get_latest_for_entry::MockGetLatestEntry as GetLatestEntry;
 
+use crate::errors::CrudError;
+use crate::retrieval::inputs::{FetchCursor, FetchPageInput};
+use crate::retrieval::outputs::{FetchPage, FetchReport};
+use crate::retrieval::utils::get_latest_for_targets;
 use crate::wire_record::WireRecord;
 use hdk::prelude::*;
-use std::convert::identity;
 
 #[cfg(feature = "mock")]
 use ::mockall::automock;
@@ -27,23 +30,198 @@ impl FetchLinks {
         link_tag: Option<LinkTag>,
         get_options: GetOptions,
     ) -> Result<Vec<WireRecord<EntryType>>, WasmError> {
+        Ok(self
+            .fetch_links_report::<EntryType>(
+                get_latest,
+                entry_hash,
+                link_type,
+                link_tag,
+                get_options,
+            )?
+            .records)
+    }
+
+    /// Like [fetch_links](FetchLinks::fetch_links) but also reports which of the link targets
+    /// could not be resolved into an entry of the given type, and why.
+    pub fn fetch_links_report<
+        EntryType: 'static + TryFrom<SerializedBytes, Error = SerializedBytesError>,
+    >(
+        &self,
+        get_latest: &GetLatestEntry,
+        entry_hash: EntryHash,
+        link_type: LinkTypeFilter,
+        link_tag: Option<LinkTag>,
+        get_options: GetOptions,
+    ) -> Result<FetchReport<EntryType>, WasmError> {
+        let mut input = GetLinksInputBuilder::try_new(entry_hash, link_type)?;
+        if let Some(link_tag_inner) = link_tag {
+            input = input.tag_prefix(link_tag_inner);
+        }
+        let targets = get_links(input.build())?
+            .into_iter()
+            .map(|link: Link| link.target)
+            .collect();
+        Ok(get_latest_for_targets::<EntryType>(
+            get_latest,
+            targets,
+            get_options,
+        ))
+    }
+
+    /// Like [fetch_links](FetchLinks::fetch_links) but only resolves a single page of the links,
+    /// so that the cost of a call doesn't grow with the number of entries linked to the EntryHash.
+    /// Links are ordered by their timestamp, then by their target, and the page starts right
+    /// after the link identified by `page.after`.
+    /// A `page.limit` of zero is rejected with [CrudError::InvalidPageLimit], since such a page
+    /// could never move the cursor forward.
+    pub fn fetch_links_page<
+        EntryType: 'static + TryFrom<SerializedBytes, Error = SerializedBytesError>,
+    >(
+        &self,
+        get_latest: &GetLatestEntry,
+        entry_hash: EntryHash,
+        link_type: LinkTypeFilter,
+        link_tag: Option<LinkTag>,
+        page: FetchPageInput,
+        get_options: GetOptions,
+    ) -> Result<FetchPage<EntryType>, WasmError> {
+        if page.limit == 0 {
+            return Err(CrudError::InvalidPageLimit.into());
+        }
         let mut input = GetLinksInputBuilder::try_new(entry_hash, link_type)?;
         if let Some(link_tag_inner) = link_tag {
             input = input.tag_prefix(link_tag_inner);
         }
-        Ok(get_links(input.build())?
+        // links which don't target an entry could never be resolved, so they don't take up room in a page
+        let mut positions: Vec<(Timestamp, EntryHash)> = get_links(input.build())?
             .into_iter()
-            .map(|link: Link| {
-                get_latest.get_latest_for_entry::<EntryType>(
-                    link.target.try_into().map_err(|_| {
-                        wasm_error!(WasmErrorInner::Guest("Target is not an entry".to_string()))
-                    })?,
-                    get_options.clone(),
-                )
+            .filter_map(|link: Link| {
+                EntryHash::try_from(link.target)
+                    .ok()
+                    .map(|target| (link.timestamp, target))
             })
-            .filter_map(Result::ok)
-            .filter_map(identity)
-            .map(|x| WireRecord::from(x))
-            .collect())
+            .collect();
+        positions.sort();
+        if let Some(after) = page.after {
+            let after = (after.timestamp, EntryHash::from(after.target));
+            positions.retain(|position| position > &after);
+        }
+        let limit = page.limit as usize;
+        let has_more = positions.len() > limit;
+        positions.truncate(limit);
+        let next = match has_more {
+            true => positions.last().map(|(timestamp, target)| FetchCursor {
+                timestamp: timestamp.to_owned(),
+                target: target.to_owned().into(),
+            }),
+            false => None,
+        };
+        let targets = positions
+            .into_iter()
+            .map(|(_, target)| target.into())
+            .collect();
+        let records = get_latest_for_targets::<EntryType>(get_latest, targets, get_options).records;
+        Ok(FetchPage { records, next })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FetchLinks;
+    use crate::crud::example::Example;
+    use crate::retrieval::get_latest_for_entry::MockGetLatestEntry;
+    use crate::retrieval::inputs::{FetchCursor, FetchPageInput};
+    use crate::test_helpers::{entry_hash, link, link_type_filter, timestamp, wire_record};
+    use hdk::prelude::*;
+
+    /// links to entries 1, 2 and 3, where 1 and 3 were linked at the same time, after 2
+    fn set_up() -> MockGetLatestEntry {
+        let mut mock_hdk = MockHdkT::new();
+        let base: AnyLinkableHash = entry_hash(100).into();
+        let links = vec![
+            link(base.clone(), entry_hash(3).into(), 10, LinkTag::new([])),
+            link(base.clone(), entry_hash(1).into(), 10, LinkTag::new([])),
+            link(base, entry_hash(2).into(), 5, LinkTag::new([])),
+        ];
+        mock_hdk
+            .expect_get_links()
+            .returning(move |_| Ok(vec![links.clone()]));
+        set_hdk(mock_hdk);
+        let mut get_latest = MockGetLatestEntry::new();
+        get_latest
+            .expect_try_get_latest_for_entry::<Example>()
+            .returning(|entry_hash, _| Ok(Ok(wire_record(entry_hash.get_raw_36()[0], 0, 0))));
+        get_latest
+    }
+
+    /// the numbers of the examples on the page, and the cursor to the next page
+    fn fetch_page(
+        get_latest: &MockGetLatestEntry,
+        after: Option<FetchCursor>,
+        limit: u32,
+    ) -> (Vec<i32>, Option<FetchCursor>) {
+        let page = FetchLinks {}
+            .fetch_links_page::<Example>(
+                get_latest,
+                entry_hash(100),
+                link_type_filter(),
+                None,
+                FetchPageInput {
+                    after,
+                    limit,
+                    get_strategy: None,
+                },
+                GetOptions::network(),
+            )
+            .unwrap();
+        let numbers = page
+            .records
+            .iter()
+            .map(|record| record.entry.number)
+            .collect();
+        (numbers, page.next)
+    }
+
+    #[test]
+    fn test_fetch_links_page() {
+        let get_latest = set_up();
+        // ordered by timestamp, and then by target
+        let (numbers, next) = fetch_page(&get_latest, None, 2);
+        assert_eq!(numbers, vec![2, 1]);
+        let cursor = FetchCursor {
+            timestamp: timestamp(10),
+            target: entry_hash(1).into(),
+        };
+        assert_eq!(next, Some(cursor.clone()));
+        // the second page picks up right after the cursor, and is the last one
+        let (numbers, next) = fetch_page(&get_latest, Some(cursor), 2);
+        assert_eq!(numbers, vec![3]);
+        assert_eq!(next, None);
+        // a page which fits everything has no next page either
+        let (numbers, next) = fetch_page(&get_latest, None, 3);
+        assert_eq!(numbers, vec![2, 1, 3]);
+        assert_eq!(next, None);
+    }
+
+    #[test]
+    fn test_fetch_links_page_rejects_zero_limit() {
+        let result = FetchLinks {}.fetch_links_page::<Example>(
+            &MockGetLatestEntry::new(),
+            entry_hash(100),
+            link_type_filter(),
+            None,
+            FetchPageInput {
+                after: None,
+                limit: 0,
+                get_strategy: None,
+            },
+            GetOptions::network(),
+        );
+        assert_eq!(
+            result.unwrap_err().error,
+            WasmErrorInner::Guest(String::from(
+                "INVALID_PAGE_LIMIT: the limit of a page must be at least 1"
+            ))
+        );
     }
 }
@@ -0,0 +1,136 @@
+use hdk::prelude::*;
+
+use crate::{
+    retrieval::utils::{root_action, update_tree, DEFAULT_MAX_UPDATE_DEPTH},
+    wire_record::Revision,
+};
+
+#[cfg(feature = "mock")]
+use ::mockall::automock;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct GetHistory {}
+#[cfg_attr(feature = "mock", automock)]
+impl GetHistory {
+    /// The companion of [get_latest_for_entry](super::get_latest_for_entry::GetLatestEntry::get_latest_for_entry)
+    /// which, instead of only returning the latest contents, returns every version of the entry
+    /// created at `action_hash`, starting with the original and followed by each of its updates
+    /// (including updates of updates), in chronological order.
+    /// Versions which can't be retrieved, aren't of type `T`, or can't be deserialized into it, are left out.
+    /// Versions made at the same time are ordered by their action hash, so that every agent sees the same history.
+    pub fn get_history_for_entry<
+        T: 'static + TryFrom<SerializedBytes, Error = SerializedBytesError>,
+    >(
+        &self,
+        action_hash: ActionHash,
+        get_options: GetOptions,
+    ) -> ExternResult<Vec<Revision<T>>> {
+        match get(action_hash, get_options.clone())? {
+            Some(record) => {
+                let root = root_action(
+                    record.signed_action().to_owned(),
+                    get_options.clone(),
+                    DEFAULT_MAX_UPDATE_DEPTH,
+                )?;
+                let tree = update_tree(&root, get_options.clone(), DEFAULT_MAX_UPDATE_DEPTH)?;
+                let mut actions = vec![root];
+                actions.extend(tree.updates);
+                actions.sort_by(|a, b| {
+                    a.action()
+                        .timestamp()
+                        .cmp(&b.action().timestamp())
+                        .then_with(|| a.as_hash().cmp(b.as_hash()))
+                });
+                let mut revisions = Vec::new();
+                for action in actions {
+                    if let Some(revision) =
+                        revision_for_action(action.as_hash().to_owned(), get_options.clone())?
+                    {
+                        revisions.push(revision);
+                    }
+                }
+                Ok(revisions)
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// the version of an entry produced by the Create or Update action at `action_hash`
+fn revision_for_action<T: 'static + TryFrom<SerializedBytes, Error = SerializedBytesError>>(
+    action_hash: ActionHash,
+    get_options: GetOptions,
+) -> ExternResult<Option<Revision<T>>> {
+    match get(action_hash, get_options)? {
+        Some(record) => match (
+            record.entry().to_app_option::<T>(),
+            record.action().entry_hash(),
+        ) {
+            (Ok(Some(entry)), Some(entry_hash)) => Ok(Some(Revision {
+                action_hash: record.action_address().to_owned().into(),
+                entry_hash: entry_hash.to_owned().into(),
+                entry,
+                author: record.action().author().to_owned().into(),
+                timestamp: record.action().timestamp(),
+            })),
+            _ => Ok(None),
+        },
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crud::example::Example;
+    use crate::test_helpers::*;
+    use crate::wire_record::Revision;
+    use hdk::prelude::*;
+
+    fn revision(n: u8, micros: i64) -> Revision<Example> {
+        Revision {
+            action_hash: action_hash(n).into(),
+            entry_hash: entry_hash(n).into(),
+            entry: Example { number: n as i32 },
+            author: agent(1).into(),
+            timestamp: timestamp(micros),
+        }
+    }
+
+    #[test]
+    fn test_get_history_for_entry() {
+        let mut mock_hdk = MockHdkT::new();
+        let store = RecordStore::default();
+        store.install(&mut mock_hdk);
+        set_hdk(mock_hdk);
+
+        let create = create_action(1, agent(1), 10);
+        // two concurrent updates, made at the same time
+        let update_3 = update_action(3, &create, agent(1), 20);
+        let update_2 = update_action(2, &create, agent(1), 20);
+        // an update which isn't an Example, followed by one which is
+        let update_4 = update_action(4, &update_2, agent(1), 30);
+        let update_5 = update_action(5, &update_4, agent(1), 40);
+        store.add(example_record(create, 1));
+        store.add(example_record(update_3, 3));
+        store.add(example_record(update_2, 2));
+        store.add(Record::new(
+            update_4,
+            Some(Entry::App(AppEntryBytes(SerializedBytes::from(
+                UnsafeBytes::from(vec![0xc1]),
+            )))),
+        ));
+        store.add(example_record(update_5, 5));
+
+        let history = super::GetHistory {}
+            .get_history_for_entry::<Example>(action_hash(1), GetOptions::local());
+        assert_eq!(
+            history,
+            Ok(vec![
+                revision(1, 10),
+                revision(2, 20),
+                revision(3, 20),
+                revision(5, 40),
+            ])
+        );
+    }
+}
@@ -1,22 +1,35 @@
 use hdk::prelude::*;
 
-use crate::{datetime_queries::utils::serialize_err, retrieval::utils::*, wire_record::WireRecord};
+use crate::{
+    errors::CrudError,
+    retrieval::conflict_resolution::{ConflictResolver, ConflictStrategy},
+    retrieval::outputs::FetchFailureReason,
+    retrieval::utils::*,
+    wire_record::WireRecord,
+};
 
 #[cfg(feature = "mock")]
 use ::mockall::automock;
 
+/// `conflict_strategy` decides which update is the latest one
+/// when an entry has been updated more than once concurrently, and
+/// `max_update_depth` limits how many updates of updates are followed
 #[derive(Debug, PartialEq, Clone)]
-pub struct GetLatestEntry {}
+pub struct GetLatestEntry {
+    pub conflict_strategy: ConflictStrategy,
+    pub max_update_depth: usize,
+}
 #[cfg_attr(feature = "mock", automock)]
 impl GetLatestEntry {
     /// If an entry at the `entry_hash` has multiple updates to itself, this
-    /// function will sort through them by timestamp in order to return the contents
+    /// function will use the `conflict_strategy` to pick one of them in order to return the contents
     /// of the latest update. It also has the special behaviour of returning the
     /// ORIGINAL ActionHash, as opposed to the ActionHash of the Action that performed
     /// that latest update. This is useful if you want hashes in your application
     /// to act consistently, almost acting as an "id" in a centralized system.
     /// It simplifies traversal of the update tree, since all updates
-    /// made by the client can reference the original, instead of updates reference updates
+    /// made by the client can reference the original, instead of updates reference updates.
+    /// Updates of updates are still followed, in case a client does update an update.
     pub fn get_latest_for_entry<
         T: 'static + TryFrom<SerializedBytes, Error = SerializedBytesError>,
     >(
@@ -24,85 +37,158 @@ impl GetLatestEntry {
         entry_hash: EntryHash,
         get_options: GetOptions,
     ) -> ExternResult<Option<WireRecord<T>>> {
+        match self.try_get_latest_for_entry::<T>(entry_hash, get_options)? {
+            Ok(wire_record) => Ok(Some(wire_record)),
+            Err(FetchFailureReason::Deserialize(message)) => {
+                Err(CrudError::Serialization(message).into())
+            }
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Just like [get_latest_for_entry](GetLatestEntry::get_latest_for_entry), but instead of `None`
+    /// it returns the reason why the latest contents of the entry could not be retrieved.
+    /// The outer `Err` is reserved for failing calls to the host.
+    pub fn try_get_latest_for_entry<
+        T: 'static + TryFrom<SerializedBytes, Error = SerializedBytesError>,
+    >(
+        &self,
+        entry_hash: EntryHash,
+        get_options: GetOptions,
+    ) -> ExternResult<Result<WireRecord<T>, FetchFailureReason>> {
         match get_details(entry_hash.clone(), get_options.clone())? {
             Some(Details::Entry(details)) => match details.entry_dht_status {
                 EntryDhtStatus::Live => {
-                    let first_action = details.actions.first().unwrap();
-                    let created_at = first_action.action().timestamp();
-                    match details.updates.len() {
-                        // pass out the action associated with this entry
-                        0 => {
-                            let updated_at = created_at.clone();
-                            let maybe_entry_and_hashes = original_action_hash_with_entry(
-                                get_action_hash(first_action.to_owned()),
-                                get_options,
-                            )?;
-                            match maybe_entry_and_hashes {
-                                Some(entry_and_hashes) => Ok(Some(WireRecord {
-                                    action_hash: entry_and_hashes.1.into(),
-                                    entry_hash: entry_and_hashes.2.into(),
-                                    entry: entry_and_hashes.0,
-                                    created_at,
-                                    updated_at,
-                                })),
-                                None => Ok(None),
-                            }
-                        }
-                        _ => {
-                            let mut sortlist = details.updates.to_vec();
-                            // unix timestamp should work for sorting
-                            sortlist.sort_by_key(|update| update.action().timestamp().as_millis());
-                            // sorts in ascending order, so take the last record
-                            let last = sortlist.last().unwrap().to_owned();
-                            let updated_at = last.action().timestamp();
-                            let maybe_entry_and_hashes = original_action_hash_with_entry(
-                                get_action_hash(last),
-                                get_options,
-                            )?;
-                            match maybe_entry_and_hashes {
-                                Some(entry_and_hashes) => Ok(Some(WireRecord {
-                                    action_hash: entry_and_hashes.1.into(),
-                                    entry_hash: entry_and_hashes.2.into(),
-                                    entry: entry_and_hashes.0,
-                                    created_at,
-                                    updated_at,
-                                })),
-                                None => Ok(None),
-                            }
-                        }
-                    }
+                    let first_action = match details.actions.first() {
+                        Some(first_action) => first_action.to_owned(),
+                        // a live entry should always have an action, but don't panic if it hasn't
+                        None => return Ok(Err(FetchFailureReason::NotFound)),
+                    };
+                    // the entry could be the content of an update, or of an update of an update,
+                    // so walk back to the original, and then out to every leaf of its update tree
+                    let root =
+                        root_action(first_action, get_options.clone(), self.max_update_depth)?;
+                    let tree = update_tree(&root, get_options.clone(), self.max_update_depth)?;
+                    let created_at = root.action().timestamp();
+                    // no updates means the original is the latest
+                    let latest = self
+                        .conflict_strategy
+                        .resolve(tree.leaves)
+                        .unwrap_or(root.clone());
+                    let updated_at = latest.action().timestamp();
+                    let created_by = root.action().author().to_owned();
+                    let updated_by = latest.action().author().to_owned();
+                    let latest_action_hash = get_action_hash(latest);
+                    let maybe_entry_and_hash =
+                        entry_for_action::<T>(latest_action_hash.clone(), get_options)?;
+                    Ok(maybe_entry_and_hash.map(|(entry, entry_hash)| WireRecord {
+                        // we DO want to return the action for the original
+                        // instead of the updated, in our case
+                        action_hash: get_action_hash(root).into(),
+                        entry_hash: entry_hash.into(),
+                        entry,
+                        created_at,
+                        updated_at,
+                        created_by: Some(created_by.into()),
+                        updated_by: Some(updated_by.into()),
+                        latest_action_hash: Some(latest_action_hash.into()),
+                    }))
                 }
-                EntryDhtStatus::Dead => Ok(None),
-                _ => Ok(None),
+                EntryDhtStatus::Dead => Ok(Err(FetchFailureReason::Deleted)),
+                _ => Ok(Err(FetchFailureReason::NotFound)),
             },
-            _ => Ok(None),
+            _ => Ok(Err(FetchFailureReason::NotFound)),
         }
     }
 }
 
-fn original_action_hash_with_entry<
-    T: 'static + TryFrom<SerializedBytes, Error = SerializedBytesError>,
->(
+impl GetLatestEntry {
+    /// resolve concurrent updates with `conflict_strategy`, following updates of updates
+    /// up to [DEFAULT_MAX_UPDATE_DEPTH] deep
+    pub fn new(conflict_strategy: ConflictStrategy) -> Self {
+        Self {
+            conflict_strategy,
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for GetLatestEntry {
+    fn default() -> Self {
+        Self {
+            conflict_strategy: ConflictStrategy::default(),
+            max_update_depth: DEFAULT_MAX_UPDATE_DEPTH,
+        }
+    }
+}
+
+/// the contents of the entry created by the Create or Update action at `action_hash`
+fn entry_for_action<T: 'static + TryFrom<SerializedBytes, Error = SerializedBytesError>>(
     action_hash: ActionHash,
     get_options: GetOptions,
-) -> ExternResult<Option<(T, ActionHash, EntryHash)>> {
+) -> ExternResult<Result<(T, EntryHash), FetchFailureReason>> {
     match get(action_hash, get_options)? {
-        Some(record) => match record.entry().to_app_option::<T>().map_err(serialize_err)? {
-            Some(entry) => Ok(Some((
-                entry,
-                match record.action() {
-                    // we DO want to return the action for the original
-                    // instead of the updated, in our case
-                    Action::Update(update) => update.original_action_address.clone(),
-                    Action::Create(_) => record.action_address().clone(),
-                    _ => {
-                        unreachable!("Can't have returned a action for a nonexistent entry")
-                    }
-                },
-                record.action().entry_hash().unwrap().to_owned(),
-            ))),
-            None => Ok(None),
+        Some(record) => match (
+            record.entry().to_app_option::<T>(),
+            record.action().entry_hash(),
+        ) {
+            (Ok(Some(entry)), Some(entry_hash)) => Ok(Ok((entry, entry_hash.to_owned()))),
+            (Ok(_), _) => Ok(Err(FetchFailureReason::WrongType)),
+            (Err(SerializedBytesError::Deserialize(message)), _)
+            | (Err(SerializedBytesError::Serialize(message)), _) => {
+                Ok(Err(FetchFailureReason::Deserialize(message)))
+            }
         },
-        None => Ok(None),
+        None => Ok(Err(FetchFailureReason::NotFound)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GetLatestEntry;
+    use crate::crud::example::Example;
+    use crate::retrieval::outputs::FetchFailureReason;
+    use crate::test_helpers::*;
+    use crate::wire_record::WireRecord;
+    use hdk::prelude::*;
+
+    #[test]
+    fn test_get_latest_follows_updates_of_updates() {
+        let mut mock_hdk = MockHdkT::new();
+        let store = RecordStore::default();
+        store.install(&mut mock_hdk);
+        set_hdk(mock_hdk);
+
+        let create = create_action(1, agent(1), 10);
+        let update_2 = update_action(2, &create, agent(1), 20);
+        // an update made with the hash of the update, instead of the original
+        let update_3 = update_action(3, &update_2, agent(2), 30);
+        store.add(example_record(create, 1));
+        store.add(example_record(update_2, 2));
+        store.add(example_record(update_3, 3));
+
+        let expected = WireRecord {
+            action_hash: action_hash(1).into(),
+            entry_hash: entry_hash(3).into(),
+            entry: Example { number: 3 },
+            created_at: timestamp(10),
+            updated_at: timestamp(30),
+            created_by: Some(agent(1).into()),
+            updated_by: Some(agent(2).into()),
+            latest_action_hash: Some(action_hash(3).into()),
+        };
+        // found the same way from any version of the entry
+        for n in [1, 2, 3] {
+            assert_eq!(
+                GetLatestEntry::default()
+                    .try_get_latest_for_entry::<Example>(entry_hash(n), GetOptions::local()),
+                Ok(Ok(expected.clone()))
+            );
+        }
+        assert_eq!(
+            GetLatestEntry::default()
+                .try_get_latest_for_entry::<Example>(entry_hash(9), GetOptions::local()),
+            Ok(Err(FetchFailureReason::NotFound))
+        );
     }
 }
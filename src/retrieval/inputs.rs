@@ -1,3 +1,4 @@
+use crate::retrieval::utils::get_options_for;
 use hdk::prelude::*;
 use holo_hash::EntryHashB64;
 
@@ -6,3 +7,60 @@ pub enum FetchOptions {
     All,
     Specific(Vec<EntryHashB64>),
 }
+
+/// The input to a fetch of entries. Either just the [FetchOptions], which are fetched
+/// with the GetOptions given to `crud!`, or the [FetchOptions] along with the
+/// GetStrategy to fetch them with, such as a fast local read before a network refresh
+#[derive(Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+#[serde(untagged)]
+pub enum FetchInput {
+    Options(FetchOptions),
+    #[serde(rename_all = "camelCase")]
+    WithStrategy {
+        fetch_options: FetchOptions,
+        get_strategy: GetStrategy,
+    },
+}
+
+impl FetchInput {
+    /// the FetchOptions, and the GetOptions to fetch them with, which are the
+    /// `default_get_options` unless a GetStrategy was given
+    pub fn into_parts(self, default_get_options: GetOptions) -> (FetchOptions, GetOptions) {
+        match self {
+            FetchInput::Options(fetch_options) => (fetch_options, default_get_options),
+            FetchInput::WithStrategy {
+                fetch_options,
+                get_strategy,
+            } => (fetch_options, get_options_for(get_strategy)),
+        }
+    }
+}
+
+impl From<FetchOptions> for FetchInput {
+    fn from(fetch_options: FetchOptions) -> Self {
+        FetchInput::Options(fetch_options)
+    }
+}
+
+/// A position in the list of links off of a Path, identifying the last
+/// link of a previously fetched page. Links are ordered by their timestamp,
+/// and then by their target hash, so that the order is stable across calls.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, SerializedBytes)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchCursor {
+    pub timestamp: Timestamp,
+    pub target: EntryHashB64,
+}
+
+/// Fetch at most `limit` entries, starting right after `after`,
+/// or from the beginning if `after` is `None`. The `limit` has to be at least 1
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, SerializedBytes)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchPageInput {
+    pub after: Option<FetchCursor>,
+    pub limit: u32,
+    /// whether to fetch from the network, or only what this agent holds locally.
+    /// Defaults to the GetOptions given to `crud!`
+    #[serde(default)]
+    pub get_strategy: Option<GetStrategy>,
+}
@@ -1,5 +1,8 @@
+pub mod conflict_resolution;
 pub mod fetch_entries;
 pub mod fetch_links;
+pub mod get_history_for_entry;
 pub mod get_latest_for_entry;
 pub mod inputs;
+pub mod outputs;
 pub mod utils;
@@ -0,0 +1,66 @@
+use hdk::prelude::*;
+use holo_hash::AnyLinkableHashB64;
+
+use crate::retrieval::inputs::FetchCursor;
+use crate::wire_record::WireRecord;
+
+/// One page of entries, along with the cursor to pass as `after`
+/// to fetch the following page. `next` is `None` when there are no more pages.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchPage<T> {
+    pub records: Vec<WireRecord<T>>,
+    pub next: Option<FetchCursor>,
+}
+
+/// Why an entry could not be included in the results of a fetch
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum FetchFailureReason {
+    /// nothing could be found at the hash
+    NotFound,
+    /// the entry exists but has been deleted
+    Deleted,
+    /// the link target is not an entry hash
+    NotAnEntry,
+    /// the entry is not an app entry, so it can't be of the requested type
+    WrongType,
+    /// the entry could not be deserialized into the requested type
+    Deserialize(String),
+    /// fetching the entry failed, for example because of a network error
+    Unavailable(String),
+}
+
+/// The hash which could not be resolved into an entry, and why
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchFailure {
+    pub hash: AnyLinkableHashB64,
+    pub reason: FetchFailureReason,
+}
+
+/// The entries which could be fetched, along with the hashes which could not,
+/// so that an empty list of records can be told apart from a list of failures.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchReport<T> {
+    pub records: Vec<WireRecord<T>>,
+    pub failures: Vec<FetchFailure>,
+}
+
+// implemented by hand because deriving it would require `T: Default`
+impl<T> Default for FetchReport<T> {
+    fn default() -> Self {
+        Self {
+            records: Vec::new(),
+            failures: Vec::new(),
+        }
+    }
+}
+
+impl<T> FetchReport<T> {
+    /// add the records and failures of another report to this one
+    pub fn extend(&mut self, other: FetchReport<T>) {
+        self.records.extend(other.records);
+        self.failures.extend(other.failures);
+    }
+}
@@ -1,7 +1,245 @@
+#[cfg(not(feature = "mock"))]
+use crate::retrieval::get_latest_for_entry::GetLatestEntry;
+#[cfg(feature = "mock")]
+use crate::retrieval::get_latest_for_entry::MockGetLatestEntry as GetLatestEntry;
+
+use crate::retrieval::outputs::{FetchFailure, FetchFailureReason, FetchReport};
+use crate::wire_record::WireRecord;
+use hdk::hdk::HDK;
 use hdk::prelude::*;
+use std::collections::HashSet;
 
 /// convert a SignedActionHashed which are like raw contents
 /// into the ActionHash of itself
 pub fn get_action_hash(signed_action_hashed: SignedActionHashed) -> ActionHash {
     signed_action_hashed.as_hash().to_owned()
 }
+
+/// resolve each of the `targets` into the latest contents of its entry, keeping track
+/// of which targets could not be resolved and why, instead of dropping them.
+/// Each record is only returned once, keyed by its original `action_hash`, since
+/// the original entry and its updates all resolve to the same record
+pub fn get_latest_for_targets<
+    T: 'static + TryFrom<SerializedBytes, Error = SerializedBytesError>,
+>(
+    get_latest: &GetLatestEntry,
+    targets: Vec<AnyLinkableHash>,
+    get_options: GetOptions,
+) -> FetchReport<T> {
+    let mut report = FetchReport::default();
+    let mut seen_targets = HashSet::new();
+    let mut seen_action_hashes = HashSet::new();
+    for target in targets {
+        // the same entry can be linked more than once, such as when identical content is created twice
+        if !seen_targets.insert(target.clone()) {
+            continue;
+        }
+        let result = match EntryHash::try_from(target.clone()) {
+            Ok(entry_hash) => {
+                match get_latest.try_get_latest_for_entry::<T>(entry_hash, get_options.clone()) {
+                    Ok(result) => result,
+                    Err(error) => Err(FetchFailureReason::Unavailable(error.to_string())),
+                }
+            }
+            Err(_) => Err(FetchFailureReason::NotAnEntry),
+        };
+        match result {
+            Ok(wire_record) => {
+                if seen_action_hashes.insert(wire_record.action_hash.clone()) {
+                    report.records.push(wire_record)
+                }
+            }
+            Err(reason) => report.failures.push(FetchFailure {
+                hash: target.into(),
+                reason,
+            }),
+        }
+    }
+    report
+}
+
+/// the GetOptions for getting with the given `get_strategy`
+pub fn get_options_for(get_strategy: GetStrategy) -> GetOptions {
+    match get_strategy {
+        GetStrategy::Local => GetOptions::local(),
+        GetStrategy::Network => GetOptions::network(),
+    }
+}
+
+/// drop the records which share an original `action_hash` with one before them, keeping the order.
+/// The same logical record can be found through the original entry and through any of its updates
+pub fn dedup_by_action_hash<T>(records: Vec<WireRecord<T>>) -> Vec<WireRecord<T>> {
+    let mut seen = HashSet::new();
+    records
+        .into_iter()
+        .filter(|record| seen.insert(record.action_hash.clone()))
+        .collect()
+}
+
+/// how many hops [root_action] and [update_tree] follow by default,
+/// which guards against pathologically long chains of updates
+pub const DEFAULT_MAX_UPDATE_DEPTH: usize = 64;
+
+/// follow the `original_action_address` of updates back to the Create action
+/// at the root of the update tree that `action` belongs to.
+/// If the root is more than `max_depth` hops away, or can't be retrieved,
+/// the furthest action that could be reached is returned.
+pub fn root_action(
+    action: SignedActionHashed,
+    get_options: GetOptions,
+    max_depth: usize,
+) -> ExternResult<SignedActionHashed> {
+    let mut current = action;
+    for _ in 0..max_depth {
+        let original_action_address = match current.action() {
+            Action::Update(update) => update.original_action_address.clone(),
+            _ => break,
+        };
+        match get(original_action_address, get_options.clone())? {
+            Some(record) => current = record.signed_action().to_owned(),
+            None => break,
+        }
+    }
+    Ok(current)
+}
+
+/// every update which descends from an action, directly or through updates of updates
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateTree {
+    /// all of the updates in the tree
+    pub updates: Vec<SignedActionHashed>,
+    /// the updates which haven't been updated themselves, meaning
+    /// they are the candidates for being the latest version
+    pub leaves: Vec<SignedActionHashed>,
+}
+
+/// walk the tree of updates descending from `root`, at most `max_depth` levels deep.
+/// The updates at the deepest level walked are considered leaves.
+/// The details of every action on one level of the tree are gotten in a single call to the host,
+/// so the number of calls grows with the depth of the tree rather than with the number of updates,
+/// and an action which is found more than once is only looked up the first time.
+pub fn update_tree(
+    root: &SignedActionHashed,
+    get_options: GetOptions,
+    max_depth: usize,
+) -> ExternResult<UpdateTree> {
+    let mut tree = UpdateTree {
+        updates: Vec::new(),
+        leaves: Vec::new(),
+    };
+    let mut seen = HashSet::new();
+    seen.insert(root.as_hash().to_owned());
+    let mut frontier = vec![root.to_owned()];
+    let mut depth = 0;
+    while !frontier.is_empty() {
+        if depth == max_depth {
+            tree.leaves.extend(frontier);
+            break;
+        }
+        let inputs = frontier
+            .iter()
+            .map(|action| GetInput::new(action.as_hash().to_owned().into(), get_options.clone()))
+            .collect();
+        let all_details = HDK.with(|h| h.borrow().get_details(inputs))?;
+        let mut next = Vec::new();
+        for (action, details) in frontier.into_iter().zip(all_details) {
+            let updates = match details {
+                Some(Details::Record(details)) => details.updates,
+                _ => Vec::new(),
+            };
+            match updates.is_empty() {
+                true => tree.leaves.push(action),
+                false => next.extend(
+                    updates
+                        .into_iter()
+                        .filter(|update| seen.insert(update.as_hash().to_owned())),
+                ),
+            }
+        }
+        tree.updates.extend(next.iter().cloned());
+        frontier = next;
+        depth += 1;
+    }
+    // the root is not an update of itself
+    tree.leaves.retain(|leaf| leaf.as_hash() != root.as_hash());
+    Ok(tree)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dedup_by_action_hash, update_tree, DEFAULT_MAX_UPDATE_DEPTH};
+    use crate::crud::example::Example;
+    use crate::test_helpers::*;
+    use hdk::prelude::*;
+
+    /// a mocked hdk which answers `get_details` from `store`, expecting to be called `times` times
+    fn set_details(store: RecordStore, times: usize) {
+        let mut mock_hdk = MockHdkT::new();
+        mock_hdk
+            .expect_get_details()
+            .times(times)
+            .returning(move |inputs| {
+                Ok(inputs
+                    .into_iter()
+                    .map(|input| store.details(&input.any_dht_hash))
+                    .collect())
+            });
+        set_hdk(mock_hdk);
+    }
+
+    #[test]
+    fn test_update_tree() {
+        let store = RecordStore::default();
+        let create = create_action(1, agent(1), 10);
+        let update_2 = update_action(2, &create, agent(1), 20);
+        let update_3 = update_action(3, &create, agent(1), 20);
+        let update_4 = update_action(4, &update_2, agent(1), 30);
+        for (action, number) in [
+            (create.clone(), 1),
+            (update_2.clone(), 2),
+            (update_3.clone(), 3),
+            (update_4.clone(), 4),
+        ] {
+            store.add(example_record(action, number));
+        }
+
+        // one call per level of the tree: the root, its updates, and the update of an update
+        set_details(store.clone(), 3);
+        let tree = update_tree(&create, GetOptions::local(), DEFAULT_MAX_UPDATE_DEPTH).unwrap();
+        assert_eq!(
+            tree.updates,
+            vec![update_2.clone(), update_3.clone(), update_4.clone()]
+        );
+        assert_eq!(tree.leaves, vec![update_3.clone(), update_4]);
+
+        // the updates at the depth limit are taken to be the leaves
+        set_details(store, 1);
+        let tree = update_tree(&create, GetOptions::local(), 1).unwrap();
+        assert_eq!(tree.updates, vec![update_2.clone(), update_3.clone()]);
+        assert_eq!(tree.leaves, vec![update_2, update_3]);
+    }
+
+    #[test]
+    fn test_dedup_by_action_hash() {
+        // record 1 found again through a later update of it
+        let mut updated = wire_record(1, 0, 20);
+        updated.entry.number = 10;
+        let records = vec![
+            wire_record(2, 0, 0),
+            wire_record(1, 0, 0),
+            wire_record(3, 0, 0),
+            updated,
+            wire_record(2, 0, 0),
+        ];
+        // the first of each is kept, in the order they were found
+        assert_eq!(
+            dedup_by_action_hash(records),
+            vec![
+                wire_record(2, 0, 0),
+                wire_record(1, 0, 0),
+                wire_record(3, 0, 0)
+            ]
+        );
+        assert_eq!(dedup_by_action_hash::<Example>(vec![]), vec![]);
+    }
+}
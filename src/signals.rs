@@ -1,7 +1,9 @@
 use hdk::prelude::*;
-use holo_hash::ActionHashB64;
+use holo_hash::{ActionHashB64, AgentPubKeyB64, EntryHashB64};
+use std::collections::BTreeMap;
 use std::fmt;
 
+use crate::errors::CrudError;
 use crate::wire_record::WireRecord;
 
 /// when sending signals, distinguish
@@ -43,7 +45,8 @@ impl fmt::Display for ActionType {
 
 /// Grant unrestricted access for this agent to receive
 /// calls to its `recv_remote_signal` endpoint via others
-/// calling `remote_signal`
+/// calling `remote_signal`. Any agent in the network can then signal this agent,
+/// see [create_assigned_receive_signal_cap_grant] to only let chosen agents do so
 pub fn create_receive_signal_cap_grant() -> ExternResult<()> {
     let mut functions = BTreeSet::new();
     functions.insert((zome_info()?.name, "recv_remote_signal".into()));
@@ -57,11 +60,172 @@ pub fn create_receive_signal_cap_grant() -> ExternResult<()> {
     Ok(())
 }
 
+/// the `recv_remote_signal` function of this zome, as granted by the receive signal cap grants
+fn receive_signal_functions() -> ExternResult<GrantedFunctions> {
+    let mut functions = BTreeSet::new();
+    functions.insert((zome_info()?.name, "recv_remote_signal".into()));
+    Ok(GrantedFunctions::Listed(functions))
+}
+
+/// access to `recv_remote_signal` with a newly generated secret, for just the `assignees` if given,
+/// or for any agent who holds the secret otherwise
+fn receive_signal_cap_grant(
+    tag: String,
+    assignees: Option<Vec<AgentPubKey>>,
+) -> ExternResult<(CapGrantEntry, CapSecret)> {
+    let secret = generate_cap_secret()?;
+    let access = match assignees {
+        None => CapAccess::Transferable { secret },
+        Some(assignees) => CapAccess::Assigned {
+            secret,
+            assignees: assignees.into_iter().collect(),
+        },
+    };
+    let grant = CapGrantEntry {
+        tag,
+        access,
+        functions: receive_signal_functions()?,
+    };
+    Ok((grant, secret))
+}
+
+/// Grant access to `recv_remote_signal` to any agent who is given the returned secret.
+/// Returns the hash of the grant, to [rotate](rotate_receive_signal_cap_grant) or
+/// [revoke](revoke_receive_signal_cap_grant) it with later.
+/// `send_remote_signal` carries no secret, so peers have to signal with [send_signal_with_secret] instead
+pub fn create_transferable_receive_signal_cap_grant(
+    tag: String,
+) -> ExternResult<(ActionHash, CapSecret)> {
+    let (grant, secret) = receive_signal_cap_grant(tag, None)?;
+    Ok((create_cap_grant(grant)?, secret))
+}
+
+/// Grant access to `recv_remote_signal` to only the `assignees`, such as the agents
+/// returned by `get_peers`, who also have to be given the returned secret.
+/// Returns the hash of the grant, to [rotate](rotate_receive_signal_cap_grant) or
+/// [revoke](revoke_receive_signal_cap_grant) it with later.
+/// `send_remote_signal` carries no secret, so peers have to signal with [send_signal_with_secret] instead
+pub fn create_assigned_receive_signal_cap_grant(
+    tag: String,
+    assignees: Vec<AgentPubKey>,
+) -> ExternResult<(ActionHash, CapSecret)> {
+    let (grant, secret) = receive_signal_cap_grant(tag, Some(assignees))?;
+    Ok((create_cap_grant(grant)?, secret))
+}
+
+/// Replace the receive signal cap grant at `grant_action_hash` with one under a new secret,
+/// for just the `assignees` if given, or for any agent who holds the new secret otherwise.
+/// The old secret stops working, such as when the set of peers changes.
+/// Returns the hash of the new grant along with its secret
+pub fn rotate_receive_signal_cap_grant(
+    grant_action_hash: ActionHash,
+    tag: String,
+    assignees: Option<Vec<AgentPubKey>>,
+) -> ExternResult<(ActionHash, CapSecret)> {
+    let (grant, secret) = receive_signal_cap_grant(tag, assignees)?;
+    Ok((update_cap_grant(grant_action_hash, grant)?, secret))
+}
+
+/// Revoke the receive signal cap grant at `grant_action_hash`, so that its secret stops working
+pub fn revoke_receive_signal_cap_grant(grant_action_hash: ActionHash) -> ExternResult<ActionHash> {
+    delete_cap_grant(grant_action_hash)
+}
+
+/// Send the `signal` to the `recv_remote_signal` function of each of the `peers`, along with the
+/// cap secret of their receive signal cap grant, which `send_remote_signal` has no way to pass.
+/// Each peer is given its own secret, as each grants access under a secret of its own.
+/// Unlike `send_remote_signal`, the peers are called one by one, and this waits for each of them.
+/// The peers which didn't take the signal, because they couldn't be reached, didn't accept their secret,
+/// or failed to receive it, are returned rather than failing the call. Only an error of the host,
+/// such as failing to encode the signal, fails it
+pub fn send_signal_with_secret<S>(
+    signal: S,
+    peers: Vec<(AgentPubKey, CapSecret)>,
+) -> ExternResult<Vec<AgentPubKey>>
+where
+    S: Serialize + std::fmt::Debug,
+{
+    let payload = ExternIO::encode(signal).map_err(CrudError::from)?;
+    let zome_name = zome_info()?.name;
+    let mut unsignalled = Vec::new();
+    for (peer, cap_secret) in peers {
+        let response = call_remote(
+            peer.clone(),
+            zome_name.clone(),
+            "recv_remote_signal".into(),
+            Some(cap_secret),
+            payload.clone(),
+        )?;
+        if !matches!(response, ZomeCallResponse::Ok(_)) {
+            unsignalled.push(peer);
+        }
+    }
+    Ok(unsignalled)
+}
+
+/// Decode a signal received by `recv_remote_signal` into the zome's signal type `S`,
+/// such as the `SignalTypes` enum unifying its [ActionSignal]s, and emit it to the UI.
+/// Payloads which aren't an `S` are rejected with [CrudError::UnknownSignal], rather than forwarded.
+/// Before emitting it, `verify_sender` is given the agent who sent the signal, and the signal,
+/// and the signal is rejected with [CrudError::UnverifiedSender] unless it returns `true`.
+/// See [crud_signals!](crate::crud_signals!) to generate `recv_remote_signal` around this
+pub fn receive<S, F>(signal: ExternIO, verify_sender: F) -> ExternResult<()>
+where
+    S: serde::de::DeserializeOwned + Serialize + std::fmt::Debug,
+    F: FnOnce(&AgentPubKey, &S) -> ExternResult<bool>,
+{
+    let signal: S = signal
+        .decode()
+        .map_err(|e| CrudError::UnknownSignal(e.to_string()))?;
+    let sender = call_info()?.provenance;
+    if !verify_sender(&sender, &signal)? {
+        return Err(CrudError::UnverifiedSender(sender.into()).into());
+    }
+    emit_signal(&signal)
+}
+
+/// A `verify_sender` for [receive] which accepts signals from any agent
+pub fn any_sender<S>(_sender: &AgentPubKey, _signal: &S) -> ExternResult<bool> {
+    Ok(true)
+}
+
+/// Generates the `recv_remote_signal` zome function, which [receive]s signals of the
+/// zome's signal type, and forwards them to the UI.
+/// ```ignore
+/// crud_signals!(SignalTypes);
+/// ```
+/// accepts signals from any agent, while
+/// ```ignore
+/// crud_signals!(SignalTypes via is_peer);
+/// ```
+/// only forwards the signals for which `is_peer(&sender, &signal)` returns `Ok(true)`,
+/// where `is_peer` has the signature `fn(&AgentPubKey, &SignalTypes) -> ExternResult<bool>`.
+/// Remember to call [create_receive_signal_cap_grant] in `init`, so that peers can reach it.
+#[macro_export]
+macro_rules! crud_signals {
+    ($signal_type:ident) => {
+        $crate::crud_signals!($signal_type via $crate::signals::any_sender);
+    };
+    ($signal_type:ident via $verify_sender:path) => {
+        #[cfg(not(feature = "exclude_zome_fns"))]
+        /// This is the exposed/public Zome function which receives the signals sent by peers,
+        /// and forwards the ones which are valid, and from verified senders, to the UI
+        #[hdk_extern]
+        pub fn recv_remote_signal(signal: ExternIO) -> ExternResult<()> {
+            $crate::signals::receive::<$signal_type, _>(signal, $verify_sender)
+        }
+    };
+}
+
 /// Distinguishes between what data structures should be passed
 /// to the UI based on different action types, like create/update/delete
 /// this will be used to send these data structures as signals to the UI
 /// When Create/Update, we will pass the actual new Entry
-/// but when doing Delete we will naturally only pass the ActionHash
+/// but when doing Delete we will naturally only pass the ActionHash.
+/// The *Batch variants are used when many entries were changed in a single call,
+/// and serialize to arrays of what the single variants would contain.
+/// Depending on the [SignalPayload], creates and updates are instead passed
+/// as [RecordHashes] only, or updates as a [RecordDiff] of the fields which changed.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 // untagged because the useful tagging is done externally on the *Signal object
 // as the tag and action
@@ -70,6 +234,136 @@ pub enum SignalData<T> {
     Create(WireRecord<T>),
     Update(WireRecord<T>),
     Delete(ActionHashB64),
+    CreateBatch(Vec<WireRecord<T>>),
+    UpdateBatch(Vec<WireRecord<T>>),
+    DeleteBatch(Vec<ActionHashB64>),
+    // the diffs come before the hashes, which they are a superset of
+    UpdateDiff(RecordDiff),
+    UpdateDiffBatch(Vec<RecordDiff>),
+    Notify(RecordHashes),
+    NotifyBatch(Vec<RecordHashes>),
+}
+
+/// How much of a created or updated record is sent to peers in a signal
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SignalPayload {
+    /// the whole [WireRecord]
+    #[default]
+    Full,
+    /// only the [RecordHashes], for clients to fetch the record if and when they need it
+    HashesOnly,
+    /// for updates, a [RecordDiff] of the fields of the entry which changed since the previous version.
+    /// Creates have no previous version, so they are sent whole
+    Diff,
+}
+
+/// The hashes which identify a record and its latest version, without its contents.
+/// It serializes with camelCase style replacement of underscores in object keys.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordHashes {
+    pub action_hash: ActionHashB64,
+    pub entry_hash: EntryHashB64,
+    pub updated_at: Timestamp,
+    #[serde(default)]
+    pub latest_action_hash: Option<ActionHashB64>,
+}
+
+impl<T> From<&WireRecord<T>> for RecordHashes {
+    fn from(wire_record: &WireRecord<T>) -> Self {
+        Self {
+            action_hash: wire_record.action_hash.clone(),
+            entry_hash: wire_record.entry_hash.clone(),
+            updated_at: wire_record.updated_at,
+            latest_action_hash: wire_record.latest_action_hash.clone(),
+        }
+    }
+}
+
+/// The fields of an entry which changed in an update, keyed by their serialized names,
+/// along with the hashes of the record, so that clients can patch the version they hold.
+/// A field which was removed has a `null` value.
+/// It serializes with camelCase style replacement of underscores in object keys.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordDiff {
+    pub action_hash: ActionHashB64,
+    pub entry_hash: EntryHashB64,
+    pub updated_at: Timestamp,
+    #[serde(default)]
+    pub updated_by: Option<AgentPubKeyB64>,
+    #[serde(default)]
+    pub latest_action_hash: Option<ActionHashB64>,
+    pub changes: BTreeMap<String, serde_json::Value>,
+}
+
+impl RecordDiff {
+    /// the diff of the `updated` record against the `previous` version of its entry
+    pub fn new<T: Serialize>(previous: &T, updated: &WireRecord<T>) -> ExternResult<Self> {
+        Ok(Self {
+            action_hash: updated.action_hash.clone(),
+            entry_hash: updated.entry_hash.clone(),
+            updated_at: updated.updated_at,
+            updated_by: updated.updated_by.clone(),
+            latest_action_hash: updated.latest_action_hash.clone(),
+            changes: diff_fields(previous, &updated.entry)?,
+        })
+    }
+}
+
+/// the top level fields which differ between `previous` and `updated`, with their `updated` values.
+/// Entries which don't serialize to a map of fields are compared whole, under the empty field name
+pub fn diff_fields<T: Serialize>(
+    previous: &T,
+    updated: &T,
+) -> ExternResult<BTreeMap<String, serde_json::Value>> {
+    let to_value = |entry: &T| {
+        serde_json::to_value(entry).map_err(|e| CrudError::Serialization(e.to_string()))
+    };
+    let mut changes = BTreeMap::new();
+    match (to_value(previous)?, to_value(updated)?) {
+        (serde_json::Value::Object(previous), serde_json::Value::Object(mut updated)) => {
+            for (field, previous_value) in previous {
+                match updated.remove(&field) {
+                    Some(value) if value == previous_value => (),
+                    Some(value) => {
+                        changes.insert(field, value);
+                    }
+                    None => {
+                        changes.insert(field, serde_json::Value::Null);
+                    }
+                }
+            }
+            // the fields which are new in the update
+            changes.extend(updated);
+        }
+        (previous, updated) => {
+            if previous != updated {
+                changes.insert(String::new(), updated);
+            }
+        }
+    }
+    Ok(changes)
+}
+
+impl<T> SignalData<T> {
+    /// the data of a signal of the `wire_record` having been created
+    pub fn created(wire_record: WireRecord<T>, signal_payload: SignalPayload) -> Self {
+        match signal_payload {
+            SignalPayload::HashesOnly => SignalData::Notify(RecordHashes::from(&wire_record)),
+            SignalPayload::Full | SignalPayload::Diff => SignalData::Create(wire_record),
+        }
+    }
+
+    /// the data of a signal of the `wire_records` having been created
+    pub fn created_batch(wire_records: Vec<WireRecord<T>>, signal_payload: SignalPayload) -> Self {
+        match signal_payload {
+            SignalPayload::HashesOnly => {
+                SignalData::NotifyBatch(wire_records.iter().map(RecordHashes::from).collect())
+            }
+            SignalPayload::Full | SignalPayload::Diff => SignalData::CreateBatch(wire_records),
+        }
+    }
 }
 
 /// This will be used to send data events as signals to the UI. All
@@ -78,19 +372,126 @@ pub enum SignalData<T> {
 /// that matches the variant for `action`. So if `action` is variant [ActionType::Create](crate::signals::ActionType::Create)
 #[doc = " then `data` should be `SignalData::Create`."]
 /// It serializes with camelCase style replacement of underscores in object keys.
+/// When deserializing, the `action` decides which variants `data` can be, since
+/// for example the data of a `CreateBatch` and of an `UpdateBatch` look the same.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
+#[serde(from = "TaggedActionSignal<T>")]
 pub struct ActionSignal<T> {
     pub entry_type: String,
     pub action: ActionType,
     pub data: SignalData<T>,
 }
 
+/// an [ActionSignal] as it is deserialized, tagged by its `action`
+#[derive(Deserialize)]
+#[serde(tag = "action")]
+enum TaggedActionSignal<T> {
+    #[serde(rename = "create")]
+    Create {
+        #[serde(rename = "entryType")]
+        entry_type: String,
+        data: CreatedData<T>,
+    },
+    #[serde(rename = "update")]
+    Update {
+        #[serde(rename = "entryType")]
+        entry_type: String,
+        data: UpdatedData<T>,
+    },
+    #[serde(rename = "delete")]
+    Delete {
+        #[serde(rename = "entryType")]
+        entry_type: String,
+        data: DeletedData,
+    },
+}
+
+/// the [SignalData] variants of a create, in the order they are tried
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CreatedData<T> {
+    Create(WireRecord<T>),
+    CreateBatch(Vec<WireRecord<T>>),
+    Notify(RecordHashes),
+    NotifyBatch(Vec<RecordHashes>),
+}
+
+/// the [SignalData] variants of an update, in the order they are tried
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum UpdatedData<T> {
+    Update(WireRecord<T>),
+    UpdateBatch(Vec<WireRecord<T>>),
+    // the diffs come before the hashes, which they are a superset of
+    UpdateDiff(RecordDiff),
+    UpdateDiffBatch(Vec<RecordDiff>),
+    Notify(RecordHashes),
+    NotifyBatch(Vec<RecordHashes>),
+}
+
+/// the [SignalData] variants of a delete
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DeletedData {
+    Delete(ActionHashB64),
+    DeleteBatch(Vec<ActionHashB64>),
+}
+
+impl<T> From<TaggedActionSignal<T>> for ActionSignal<T> {
+    fn from(tagged: TaggedActionSignal<T>) -> Self {
+        let (entry_type, action, data) = match tagged {
+            TaggedActionSignal::Create { entry_type, data } => {
+                let data = match data {
+                    CreatedData::Create(wire_record) => SignalData::Create(wire_record),
+                    CreatedData::CreateBatch(wire_records) => SignalData::CreateBatch(wire_records),
+                    CreatedData::Notify(hashes) => SignalData::Notify(hashes),
+                    CreatedData::NotifyBatch(hashes) => SignalData::NotifyBatch(hashes),
+                };
+                (entry_type, ActionType::Create, data)
+            }
+            TaggedActionSignal::Update { entry_type, data } => {
+                let data = match data {
+                    UpdatedData::Update(wire_record) => SignalData::Update(wire_record),
+                    UpdatedData::UpdateBatch(wire_records) => SignalData::UpdateBatch(wire_records),
+                    UpdatedData::UpdateDiff(diff) => SignalData::UpdateDiff(diff),
+                    UpdatedData::UpdateDiffBatch(diffs) => SignalData::UpdateDiffBatch(diffs),
+                    UpdatedData::Notify(hashes) => SignalData::Notify(hashes),
+                    UpdatedData::NotifyBatch(hashes) => SignalData::NotifyBatch(hashes),
+                };
+                (entry_type, ActionType::Update, data)
+            }
+            TaggedActionSignal::Delete { entry_type, data } => {
+                let data = match data {
+                    DeletedData::Delete(action_hash) => SignalData::Delete(action_hash),
+                    DeletedData::DeleteBatch(action_hashes) => {
+                        SignalData::DeleteBatch(action_hashes)
+                    }
+                };
+                (entry_type, ActionType::Delete, data)
+            }
+        };
+        Self {
+            entry_type,
+            action,
+            data,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::create_receive_signal_cap_grant;
+    use super::{
+        any_sender, create_assigned_receive_signal_cap_grant, create_receive_signal_cap_grant,
+        create_transferable_receive_signal_cap_grant, diff_fields, receive,
+        revoke_receive_signal_cap_grant, rotate_receive_signal_cap_grant, send_signal_with_secret,
+        ActionSignal, ActionType, RecordDiff, RecordHashes, SignalData,
+    };
+    use crate::crud::example::Example;
+    use crate::test_helpers::{action_hash, agent, wire_record};
     use ::fixt::prelude::*;
     use hdk::prelude::*;
+    use std::sync::{Arc, Mutex};
 
     #[test]
     fn test_create_receive_signal_cap_grant() {
@@ -104,10 +505,9 @@ mod tests {
             .times(1)
             .return_const(Ok(zome_info.clone()));
         // create_cap_grant calls just `create` under the hood
-        let mut functions: GrantedFunctions = BTreeSet::new();
-        functions.insert((zome_info.name, "recv_remote_signal".into()));
+        let functions = recv_remote_signal(&zome_info);
         let expected = CreateInput::new(
-            EntryDefId::CapGrant,
+            EntryDefLocation::CapGrant,
             EntryVisibility::Private,
             Entry::CapGrant(CapGrantEntry {
                 tag: "".into(),
@@ -128,4 +528,251 @@ mod tests {
         let result = create_receive_signal_cap_grant();
         assert_eq!(result.is_ok(), true);
     }
+
+    #[test]
+    fn test_create_assigned_receive_signal_cap_grant() {
+        let zome_info = fixt!(ZomeInfo);
+        // the secret is generated from random bytes
+        let mut mock_hdk = mock_hdk_with_secret(&zome_info);
+        let secret = CapSecret::from([7; 64]);
+        let assignee = fixt!(AgentPubKey);
+        let functions = recv_remote_signal(&zome_info);
+        let mut assignees = BTreeSet::new();
+        assignees.insert(assignee.clone());
+        let expected = CreateInput::new(
+            EntryDefLocation::CapGrant,
+            EntryVisibility::Private,
+            Entry::CapGrant(CapGrantEntry {
+                tag: "peers".into(),
+                // only the assignees, and only with the secret
+                access: CapAccess::Assigned { secret, assignees },
+                functions,
+            }),
+            ChainTopOrdering::Strict,
+        );
+        let action_hash = fixt!(ActionHash);
+        mock_hdk
+            .expect_create()
+            .with(mockall::predicate::eq(expected))
+            .times(1)
+            .return_const(Ok(action_hash.clone()));
+        set_hdk(mock_hdk);
+        let result = create_assigned_receive_signal_cap_grant("peers".into(), vec![assignee]);
+        assert_eq!(result, Ok((action_hash, secret)));
+    }
+
+    /// a mocked hdk which generates the secret `[7; 64]`, and names the zome by `zome_info`
+    fn mock_hdk_with_secret(zome_info: &ZomeInfo) -> MockHdkT {
+        let mut mock_hdk = MockHdkT::new();
+        mock_hdk
+            .expect_random_bytes()
+            .times(1)
+            .return_const(Ok(Bytes::from(vec![7; 64])));
+        mock_hdk
+            .expect_zome_info()
+            .times(1)
+            .return_const(Ok(zome_info.clone()));
+        mock_hdk
+    }
+
+    fn recv_remote_signal(zome_info: &ZomeInfo) -> GrantedFunctions {
+        let mut functions = BTreeSet::new();
+        functions.insert((zome_info.name.clone(), "recv_remote_signal".into()));
+        GrantedFunctions::Listed(functions)
+    }
+
+    #[test]
+    fn test_create_transferable_receive_signal_cap_grant() {
+        let zome_info = fixt!(ZomeInfo);
+        let mut mock_hdk = mock_hdk_with_secret(&zome_info);
+        let secret = CapSecret::from([7; 64]);
+        let expected = CreateInput::new(
+            EntryDefLocation::CapGrant,
+            EntryVisibility::Private,
+            Entry::CapGrant(CapGrantEntry {
+                tag: "anyone".into(),
+                // any agent who holds the secret
+                access: CapAccess::Transferable { secret },
+                functions: recv_remote_signal(&zome_info),
+            }),
+            ChainTopOrdering::Strict,
+        );
+        mock_hdk
+            .expect_create()
+            .with(mockall::predicate::eq(expected))
+            .times(1)
+            .return_const(Ok(action_hash(1)));
+        set_hdk(mock_hdk);
+        let result = create_transferable_receive_signal_cap_grant("anyone".into());
+        assert_eq!(result, Ok((action_hash(1), secret)));
+    }
+
+    #[test]
+    fn test_rotate_receive_signal_cap_grant() {
+        let zome_info = fixt!(ZomeInfo);
+        let mut mock_hdk = mock_hdk_with_secret(&zome_info);
+        let secret = CapSecret::from([7; 64]);
+        let mut assignees = BTreeSet::new();
+        assignees.insert(agent(2));
+        let expected = Entry::CapGrant(CapGrantEntry {
+            tag: "peers".into(),
+            // the new set of peers, under the new secret
+            access: CapAccess::Assigned { secret, assignees },
+            functions: recv_remote_signal(&zome_info),
+        });
+        mock_hdk
+            .expect_update()
+            .withf(move |input| {
+                input.original_action_address == action_hash(1) && input.entry == expected
+            })
+            .times(1)
+            .return_const(Ok(action_hash(2)));
+        set_hdk(mock_hdk);
+        let result =
+            rotate_receive_signal_cap_grant(action_hash(1), "peers".into(), Some(vec![agent(2)]));
+        assert_eq!(result, Ok((action_hash(2), secret)));
+    }
+
+    #[test]
+    fn test_revoke_receive_signal_cap_grant() {
+        let mut mock_hdk = MockHdkT::new();
+        mock_hdk
+            .expect_delete()
+            .withf(|input| input.deletes_action_hash == action_hash(1))
+            .times(1)
+            .return_const(Ok(action_hash(2)));
+        set_hdk(mock_hdk);
+        assert_eq!(
+            revoke_receive_signal_cap_grant(action_hash(1)),
+            Ok(action_hash(2))
+        );
+    }
+
+    #[test]
+    fn test_send_signal_with_secret() {
+        let zome_info = fixt!(ZomeInfo);
+        let mut mock_hdk = MockHdkT::new();
+        mock_hdk
+            .expect_zome_info()
+            .times(1)
+            .return_const(Ok(zome_info.clone()));
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let made = calls.clone();
+        // the second peer can't be reached
+        mock_hdk.expect_call().returning(move |inputs| {
+            Ok(inputs
+                .into_iter()
+                .map(|call| {
+                    let reached = call.target == CallTarget::NetworkAgent(agent(1));
+                    made.lock().unwrap().push(call);
+                    match reached {
+                        true => ZomeCallResponse::Ok(ExternIO::encode(()).unwrap()),
+                        false => ZomeCallResponse::NetworkError("unreachable".to_string()),
+                    }
+                })
+                .collect())
+        });
+        set_hdk(mock_hdk);
+        let (first, second) = (CapSecret::from([1; 64]), CapSecret::from([2; 64]));
+        let result = send_signal_with_secret("hello", vec![(agent(1), first), (agent(2), second)]);
+        // which is reported, without failing the call
+        assert_eq!(result, Ok(vec![agent(2)]));
+        // each peer is called with its own secret
+        let calls = calls.lock().unwrap();
+        assert_eq!(calls.len(), 2);
+        for (call, (peer, secret)) in calls.iter().zip([(agent(1), first), (agent(2), second)]) {
+            assert_eq!(call.target, CallTarget::NetworkAgent(peer));
+            assert_eq!(call.zome_name, zome_info.name);
+            assert_eq!(call.fn_name, "recv_remote_signal".into());
+            assert_eq!(call.cap_secret, Some(secret));
+            assert_eq!(call.payload, ExternIO::encode("hello").unwrap());
+        }
+    }
+
+    #[test]
+    fn test_diff_fields() {
+        #[derive(Serialize)]
+        struct Task {
+            title: String,
+            done: bool,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            note: Option<String>,
+        }
+        let previous = Task {
+            title: "write tests".to_string(),
+            done: false,
+            note: Some("soon".to_string()),
+        };
+        let updated = Task {
+            title: "write tests".to_string(),
+            done: true,
+            note: None,
+        };
+        let changes = diff_fields(&previous, &updated).unwrap();
+        // the unchanged title is left out, and the removed note is null
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes.get("done"), Some(&serde_json::Value::Bool(true)));
+        assert_eq!(changes.get("note"), Some(&serde_json::Value::Null));
+        assert_eq!(diff_fields(&updated, &updated).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_receive_rejects_malformed_signal() {
+        // the payload is rejected before anything is asked of the host
+        let signal = ExternIO::encode("not an action signal").unwrap();
+        let result = receive::<ActionSignal<u32>, _>(signal, any_sender);
+        match result {
+            Err(error) => match error.error {
+                WasmErrorInner::Guest(message) => assert!(message.starts_with("UNKNOWN_SIGNAL: ")),
+                other => panic!("unexpected error {:?}", other),
+            },
+            Ok(_) => panic!("a malformed signal was emitted"),
+        }
+    }
+
+    #[test]
+    fn test_action_signal_round_trip() {
+        let records = vec![wire_record(1, 10, 10), wire_record(2, 20, 30)];
+        let hashes: Vec<RecordHashes> = records.iter().map(RecordHashes::from).collect();
+        let diffs: Vec<RecordDiff> = records
+            .iter()
+            .map(|record| RecordDiff::new(&Example { number: 0 }, record).unwrap())
+            .collect();
+        let action_hashes: Vec<ActionHashB64> = records
+            .iter()
+            .map(|record| record.action_hash.clone())
+            .collect();
+        let signals = vec![
+            (ActionType::Create, SignalData::Create(records[0].clone())),
+            (ActionType::Create, SignalData::CreateBatch(records.clone())),
+            (ActionType::Create, SignalData::Notify(hashes[0].clone())),
+            (ActionType::Create, SignalData::NotifyBatch(hashes.clone())),
+            (ActionType::Update, SignalData::Update(records[0].clone())),
+            (ActionType::Update, SignalData::UpdateBatch(records.clone())),
+            (ActionType::Update, SignalData::UpdateDiff(diffs[0].clone())),
+            (ActionType::Update, SignalData::UpdateDiffBatch(diffs)),
+            (ActionType::Update, SignalData::Notify(hashes[0].clone())),
+            (ActionType::Update, SignalData::NotifyBatch(hashes)),
+            (
+                ActionType::Delete,
+                SignalData::Delete(action_hashes[0].clone()),
+            ),
+            (ActionType::Delete, SignalData::DeleteBatch(action_hashes)),
+        ];
+        for (action, data) in signals {
+            let signal = ActionSignal {
+                entry_type: "example".into(),
+                action,
+                data,
+            };
+            // as it is sent to peers
+            let decoded: ActionSignal<Example> =
+                ExternIO::encode(signal.clone()).unwrap().decode().unwrap();
+            assert_eq!(decoded, signal);
+            // as it is emitted to, and could be sent back by, a UI
+            let decoded: ActionSignal<Example> =
+                serde_json::from_str(&serde_json::to_string(&signal).unwrap()).unwrap();
+            assert_eq!(decoded, signal);
+        }
+    }
 }
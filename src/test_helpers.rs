@@ -0,0 +1,334 @@
+//! Shared building blocks for the unit tests, which run against a mocked hdk.
+//! Hashes are made up from a single byte so that they are easy to tell apart, and links are kept in a
+//! [LinkStore] so that code which walks paths can be tested without mocking every call to the host.
+use crate::crud::example::Example;
+use crate::datetime_queries::inputs::FetchEntriesTime;
+use crate::datetime_queries::utils::day_path_from_date;
+use crate::wire_record::WireRecord;
+use hdk::prelude::*;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+pub fn action_hash(n: u8) -> ActionHash {
+    ActionHash::from_raw_36(vec![n; 36])
+}
+
+pub fn entry_hash(n: u8) -> EntryHash {
+    EntryHash::from_raw_36(vec![n; 36])
+}
+
+pub fn agent(n: u8) -> AgentPubKey {
+    AgentPubKey::from_raw_36(vec![n; 36])
+}
+
+/// a stand in for hashing an entry, which is deterministic, so that the same path always gets the same hash
+pub fn fake_entry_hash(entry: &Entry) -> EntryHash {
+    let mut hasher = DefaultHasher::new();
+    format!("{:?}", entry).hash(&mut hasher);
+    let bytes = hasher.finish().to_le_bytes();
+    EntryHash::from_raw_36(bytes.iter().cycle().take(36).cloned().collect())
+}
+
+/// to be given to `expect_hash().returning(..)`
+pub fn fake_hash(input: HashInput) -> ExternResult<HashOutput> {
+    match input {
+        HashInput::Entry(entry) => Ok(HashOutput::Entry(fake_entry_hash(&entry))),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "unsupported hash input {:?}",
+            other
+        )))),
+    }
+}
+
+/// a link type which doesn't need `zome_info` to be scoped
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestLinkType;
+
+impl TryFrom<TestLinkType> for ScopedLinkType {
+    type Error = WasmError;
+    fn try_from(_: TestLinkType) -> Result<Self, Self::Error> {
+        Ok(ScopedLinkType {
+            zome_index: ZomeIndex(0),
+            zome_type: LinkType(0),
+        })
+    }
+}
+
+/// the entry def of [Example], which doesn't need `zome_info` to be scoped
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestEntryType;
+
+impl TryFrom<TestEntryType> for ScopedEntryDefIndex {
+    type Error = WasmError;
+    fn try_from(_: TestEntryType) -> Result<Self, Self::Error> {
+        Ok(ScopedEntryDefIndex {
+            zome_index: ZomeIndex(0),
+            zome_type: EntryDefIndex(0),
+        })
+    }
+}
+
+pub fn fetch_time(year: i32, month: u32, day: u32, hour: Option<u32>) -> FetchEntriesTime {
+    FetchEntriesTime {
+        year,
+        month,
+        day,
+        hour,
+        minute: None,
+        second: None,
+    }
+}
+
+pub fn link_type_filter() -> LinkTypeFilter {
+    LinkTypeFilter::single_type(ZomeIndex(0), LinkType(0))
+}
+
+pub fn timestamp(micros: i64) -> Timestamp {
+    Timestamp::from_micros(micros)
+}
+
+pub fn link(base: AnyLinkableHash, target: AnyLinkableHash, micros: i64, tag: LinkTag) -> Link {
+    Link {
+        author: agent(0),
+        base,
+        target,
+        timestamp: timestamp(micros),
+        zome_index: ZomeIndex(0),
+        link_type: LinkType(0),
+        tag,
+        create_link_hash: action_hash(0),
+    }
+}
+
+/// an [Example] record, identified by `n`, created and updated at the given times
+pub fn wire_record(n: u8, created_at: i64, updated_at: i64) -> WireRecord<Example> {
+    WireRecord {
+        action_hash: action_hash(n).into(),
+        entry_hash: entry_hash(n).into(),
+        entry: Example { number: n as i32 },
+        created_at: timestamp(created_at),
+        updated_at: timestamp(updated_at),
+        created_by: None,
+        updated_by: None,
+        latest_action_hash: None,
+    }
+}
+
+/// the entry type of [TestEntryType]
+pub fn example_entry_type() -> EntryType {
+    EntryType::App(AppEntryDef::new(
+        EntryDefIndex(0),
+        ZomeIndex(0),
+        EntryVisibility::Public,
+    ))
+}
+
+pub fn signed(action: Action, hash: ActionHash) -> SignedActionHashed {
+    SignedActionHashed::with_presigned(
+        ActionHashed::with_pre_hashed(action, hash),
+        Signature([0; 64]),
+    )
+}
+
+/// a Create of an [Example] entry, at `action_hash(n)`
+pub fn create_action(n: u8, author: AgentPubKey, micros: i64) -> SignedActionHashed {
+    signed(
+        Action::Create(Create {
+            author,
+            timestamp: timestamp(micros),
+            action_seq: 4,
+            prev_action: action_hash(0),
+            entry_type: example_entry_type(),
+            entry_hash: entry_hash(n),
+            weight: EntryRateWeight::default(),
+        }),
+        action_hash(n),
+    )
+}
+
+/// an Update of the action `original`, to an [Example] entry, at `action_hash(n)`
+pub fn update_action(
+    n: u8,
+    original: &SignedActionHashed,
+    author: AgentPubKey,
+    micros: i64,
+) -> SignedActionHashed {
+    signed(
+        Action::Update(Update {
+            author,
+            timestamp: timestamp(micros),
+            action_seq: 5,
+            prev_action: action_hash(0),
+            original_action_address: original.as_hash().to_owned(),
+            original_entry_address: original.action().entry_hash().unwrap().to_owned(),
+            entry_type: example_entry_type(),
+            entry_hash: entry_hash(n),
+            weight: EntryRateWeight::default(),
+        }),
+        action_hash(n),
+    )
+}
+
+/// the record of `action`, with the [Example] entry numbered `number`
+pub fn example_record(action: SignedActionHashed, number: i32) -> Record {
+    Record::new(action, Some(Entry::try_from(Example { number }).unwrap()))
+}
+
+/// An in memory store of links, to answer the `create_link` and `get_links` calls of a mocked hdk
+#[derive(Clone, Default)]
+pub struct LinkStore(Arc<Mutex<Vec<Link>>>);
+
+impl LinkStore {
+    /// answer `hash`, `create_link` and `get_links` from this store.
+    /// Links are matched by their base, and by the prefix of their tag if one is asked for
+    pub fn install(&self, mock_hdk: &mut MockHdkT) {
+        mock_hdk.expect_hash().returning(fake_hash);
+        let store = self.clone();
+        mock_hdk.expect_create_link().returning(move |input| {
+            store.add(link(input.base_address, input.target_address, 0, input.tag));
+            Ok(action_hash(0))
+        });
+        let store = self.clone();
+        mock_hdk.expect_get_links().returning(move |inputs| {
+            Ok(inputs
+                .into_iter()
+                .map(|input| store.get(&input.base_address, input.tag_prefix.as_ref()))
+                .collect())
+        });
+    }
+
+    pub fn add(&self, link: Link) {
+        self.0.lock().unwrap().push(link);
+    }
+
+    pub fn get(&self, base: &AnyLinkableHash, tag_prefix: Option<&LinkTag>) -> Vec<Link> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|link| &link.base == base)
+            .filter(|link| match tag_prefix {
+                Some(prefix) => link.tag.0.starts_with(&prefix.0),
+                None => true,
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// the targets of the links off of `base`, in the order they were created
+    pub fn targets(&self, base: &AnyLinkableHash) -> Vec<AnyLinkableHash> {
+        self.get(base, None)
+            .into_iter()
+            .map(|link| link.target)
+            .collect()
+    }
+}
+
+/// set a mocked hdk whose links are kept in the returned store
+pub fn set_link_store() -> LinkStore {
+    let mut mock_hdk = MockHdkT::new();
+    let store = LinkStore::default();
+    store.install(&mut mock_hdk);
+    set_hdk(mock_hdk);
+    store
+}
+
+/// add the path of a day to the time index under `base_component`, as indexing an entry would
+pub fn index_day(base_component: &str, year: i32, month: u32, day: u32) {
+    day_path_from_date(TestLinkType, base_component.to_string(), year, month, day)
+        .unwrap()
+        .ensure()
+        .unwrap();
+}
+
+/// An in memory store of records, to answer the `get` and `get_details` calls of a mocked hdk.
+/// The updates in the details of a record, or of an entry, are the stored records which update it
+#[derive(Clone, Default)]
+pub struct RecordStore(Arc<Mutex<Vec<Record>>>);
+
+impl RecordStore {
+    pub fn install(&self, mock_hdk: &mut MockHdkT) {
+        let store = self.clone();
+        mock_hdk.expect_get().returning(move |inputs| {
+            Ok(inputs
+                .into_iter()
+                .map(|input| store.get(&input.any_dht_hash))
+                .collect())
+        });
+        let store = self.clone();
+        mock_hdk.expect_get_details().returning(move |inputs| {
+            Ok(inputs
+                .into_iter()
+                .map(|input| store.details(&input.any_dht_hash))
+                .collect())
+        });
+    }
+
+    pub fn add(&self, record: Record) {
+        self.0.lock().unwrap().push(record);
+    }
+
+    /// the record at an action hash, or the first record of an entry hash
+    pub fn get(&self, hash: &AnyDhtHash) -> Option<Record> {
+        self.records_at(hash).into_iter().next()
+    }
+
+    pub fn details(&self, hash: &AnyDhtHash) -> Option<Details> {
+        let records = self.records_at(hash);
+        let record = records.first()?.to_owned();
+        let updates = records
+            .iter()
+            .flat_map(|record| self.updates_of(record.action_address()))
+            .collect();
+        match hash == &AnyDhtHash::from(record.action_address().to_owned()) {
+            true => Some(Details::Record(RecordDetails {
+                record,
+                validation_status: ValidationStatus::Valid,
+                deletes: Vec::new(),
+                updates,
+            })),
+            false => Some(Details::Entry(EntryDetails {
+                entry: record.entry().as_option()?.to_owned(),
+                actions: records
+                    .iter()
+                    .map(|record| record.signed_action().to_owned())
+                    .collect(),
+                rejected_actions: Vec::new(),
+                deletes: Vec::new(),
+                updates,
+                entry_dht_status: EntryDhtStatus::Live,
+            })),
+        }
+    }
+
+    fn records_at(&self, hash: &AnyDhtHash) -> Vec<Record> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|record| {
+                hash == &AnyDhtHash::from(record.action_address().to_owned())
+                    || record
+                        .action()
+                        .entry_hash()
+                        .map(|entry_hash| hash == &AnyDhtHash::from(entry_hash.to_owned()))
+                        .unwrap_or(false)
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn updates_of(&self, action_hash: &ActionHash) -> Vec<SignedActionHashed> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|record| match record.action() {
+                Action::Update(update) => &update.original_action_address == action_hash,
+                _ => false,
+            })
+            .map(|record| record.signed_action().to_owned())
+            .collect()
+    }
+}
@@ -0,0 +1,381 @@
+//! Validation helpers for the integrity zome of an entry type, built on hdi alone, like the integrity zome itself
+use hdi::hash_path::path::{Path, DHT_PREFIX};
+use hdi::prelude::*;
+
+/// A rule which the creation of an entry of type `T` has to follow
+pub enum CreateRule<T> {
+    /// any rule, given the create action and the new entry
+    Custom(fn(&EntryCreationAction, &T) -> ExternResult<ValidateCallbackResult>),
+}
+
+/// A rule which an update of an entry of type `T` has to follow,
+/// on top of having to update an entry of the same type
+pub enum UpdateRule<T> {
+    /// only the author of the original entry can update it
+    AuthorOnly,
+    /// the named field can't be changed by an update.
+    /// The function returns whether the field is equal in the original and the updated entry
+    ImmutableField(&'static str, fn(&T, &T) -> bool),
+    /// any rule, given the update action, the updated entry, the original action and the original entry
+    Custom(fn(&Update, &T, &Action, &T) -> ExternResult<ValidateCallbackResult>),
+}
+
+/// A rule which a delete of an entry of type `T` has to follow,
+/// on top of having to delete an entry of that type
+pub enum DeleteRule<T> {
+    /// only the author of the original entry can delete it
+    AuthorOnly,
+    /// any rule, given the delete action, the original action and the original entry
+    Custom(fn(&Delete, &Action, &T) -> ExternResult<ValidateCallbackResult>),
+}
+
+/// validate the creation of an entry of type `T` against the `rules`
+pub fn validate_create_of_type<T>(
+    action: &EntryCreationAction,
+    entry: &T,
+    rules: &[CreateRule<T>],
+) -> ExternResult<ValidateCallbackResult> {
+    for rule in rules {
+        let result = match rule {
+            CreateRule::Custom(custom) => custom(action, entry)?,
+        };
+        if result != ValidateCallbackResult::Valid {
+            return Ok(result);
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// validate that an update of an entry of type `T` targets an entry of the same type,
+/// and that it follows the `rules`
+pub fn validate_update_of_type<T>(
+    action: &Update,
+    entry: &T,
+    rules: &[UpdateRule<T>],
+) -> ExternResult<ValidateCallbackResult>
+where
+    T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+    let original_record = must_get_valid_record(action.original_action_address.clone())?;
+    if original_record.action().entry_type() != Some(&action.entry_type) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "an update must target an entry of the same type",
+        )));
+    }
+    let original_entry = match original_record.entry().to_app_option::<T>() {
+        Ok(Some(original_entry)) => original_entry,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "an update must target an entry of the same type",
+            )))
+        }
+    };
+    for rule in rules {
+        let result = match rule {
+            UpdateRule::AuthorOnly => match original_record.action().author() == &action.author {
+                true => ValidateCallbackResult::Valid,
+                false => ValidateCallbackResult::Invalid(String::from(
+                    "only the author of an entry can update it",
+                )),
+            },
+            UpdateRule::ImmutableField(field, is_unchanged) => {
+                match is_unchanged(&original_entry, entry) {
+                    true => ValidateCallbackResult::Valid,
+                    false => ValidateCallbackResult::Invalid(format!(
+                        "the field `{}` can't be updated",
+                        field
+                    )),
+                }
+            }
+            UpdateRule::Custom(custom) => {
+                custom(action, entry, original_record.action(), &original_entry)?
+            }
+        };
+        if result != ValidateCallbackResult::Valid {
+            return Ok(result);
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// whether `entry_type` is the app entry type that `entry_def`, such as a variant of the
+/// `UnitEntryTypes` of the integrity zome, is scoped to
+fn is_entry_def<U, E>(entry_type: Option<&EntryType>, entry_def: U) -> ExternResult<bool>
+where
+    ScopedEntryDefIndex: TryFrom<U, Error = E>,
+    WasmError: From<E>,
+{
+    let scoped = ScopedEntryDefIndex::try_from(entry_def)?;
+    Ok(match entry_type {
+        Some(EntryType::App(app_entry_def)) => {
+            app_entry_def.zome_index == scoped.zome_index
+                && app_entry_def.entry_index == scoped.zome_type
+        }
+        _ => false,
+    })
+}
+
+/// validate that a delete targets an entry of type `T`, defined as `entry_def`, and that it follows the `rules`.
+/// The entry type of the deleted record is compared with `entry_def`, since entries of
+/// other types could deserialize into a `T` as well
+pub fn validate_delete_of_type<T, U, E>(
+    action: &Delete,
+    entry_def: U,
+    rules: &[DeleteRule<T>],
+) -> ExternResult<ValidateCallbackResult>
+where
+    T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+    ScopedEntryDefIndex: TryFrom<U, Error = E>,
+    WasmError: From<E>,
+{
+    let original_record = must_get_valid_record(action.deletes_address.clone())?;
+    if !is_entry_def(original_record.action().entry_type(), entry_def)? {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "a delete must target an entry of this type",
+        )));
+    }
+    let original_entry = match original_record.entry().to_app_option::<T>() {
+        Ok(Some(original_entry)) => original_entry,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "a delete must target an entry of this type",
+            )))
+        }
+    };
+    for rule in rules {
+        let result = match rule {
+            DeleteRule::AuthorOnly => match original_record.action().author() == &action.author {
+                true => ValidateCallbackResult::Valid,
+                false => ValidateCallbackResult::Invalid(String::from(
+                    "only the author of an entry can delete it",
+                )),
+            },
+            DeleteRule::Custom(custom) => {
+                custom(action, original_record.action(), &original_entry)?
+            }
+        };
+        if result != ValidateCallbackResult::Valid {
+            return Ok(result);
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// validate that a link, such as one from the Path of an entry type, targets an entry of type `T`.
+/// Links from a Path to one of its child Paths, as made by `TypedPath::ensure`
+/// for the Path of the type and for the time index, are valid too.
+/// Only the hash of the target entry is known, and not the action which created it,
+/// so unlike [validate_delete_of_type] its entry type can't be compared,
+/// and the target is checked by deserializing it instead
+pub fn validate_link_to_type<T>(
+    target_address: AnyLinkableHash,
+    tag: &LinkTag,
+) -> ExternResult<ValidateCallbackResult>
+where
+    T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+    let entry_hash = match EntryHash::try_from(target_address) {
+        Ok(entry_hash) => entry_hash,
+        Err(_) => {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "the link must target an entry",
+            )))
+        }
+    };
+    let entry = RecordEntry::Present(must_get_entry(entry_hash)?.content);
+    let is_path_link = tag.0.first() == Some(&DHT_PREFIX);
+    if is_path_link && matches!(entry.to_app_option::<Path>(), Ok(Some(_))) {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+    match entry.to_app_option::<T>() {
+        Ok(Some(_)) => Ok(ValidateCallbackResult::Valid),
+        _ => Ok(ValidateCallbackResult::Invalid(String::from(
+            "the link must target an entry of this type",
+        ))),
+    }
+}
+
+/// A macro to generate the validation functions, for use in an integrity zome,
+/// which go along with the zome functions generated by [crud!](crate::crud!).
+/// Call them from the `validate` callback of the integrity zome, for the ops relating to the entry type.
+/// They make sure that updates and deletes target entries of the same type, that links point at entries of
+/// the type, and that any of the given [CreateRule]s, [UpdateRule]s and [DeleteRule]s are followed.
+/// ```ignore
+/// use hdi::prelude::*;
+/// use hdk_crud::validation::*;
+///
+/// crud_validation!(Example, UnitEntryTypes::Example, example);
+///
+/// // or with rules
+/// crud_validation!(
+///   Example,
+///   UnitEntryTypes::Example,
+///   example,
+///   create_rules: [],
+///   update_rules: [
+///     UpdateRule::AuthorOnly,
+///     UpdateRule::ImmutableField("number", |a, b| a.number == b.number)
+///   ],
+///   delete_rules: [DeleteRule::AuthorOnly]
+/// );
+/// ```
+/// would generate `validate_create_example`, `validate_update_example`,
+/// `validate_delete_example` and `validate_create_link_example`, where `UnitEntryTypes::Example`
+/// is the entry def of `Example` that deleted entries are checked against.
+#[macro_export]
+macro_rules! crud_validation {
+    (
+      $crud_type:ident, $entry_def:expr, $i:ident
+    ) => {
+        $crate::crud_validation!(
+          $crud_type, $entry_def, $i, create_rules: [], update_rules: [], delete_rules: []
+        );
+    };
+    (
+      $crud_type:ident, $entry_def:expr, $i:ident,
+      create_rules: [$($create_rule:expr),* $(,)?],
+      update_rules: [$($update_rule:expr),* $(,)?],
+      delete_rules: [$($delete_rule:expr),* $(,)?]
+    ) => {
+        ::paste::paste! {
+          /// Validate the creation of an entry of this type
+          pub fn [<validate_create_ $i>](
+            action: EntryCreationAction,
+            entry: $crud_type,
+          ) -> ExternResult<ValidateCallbackResult> {
+            let rules: &[$crate::validation::CreateRule<$crud_type>] = &[$($create_rule),*];
+            $crate::validation::validate_create_of_type(&action, &entry, rules)
+          }
+
+          /// Validate an update of an entry of this type
+          pub fn [<validate_update_ $i>](
+            action: Update,
+            entry: $crud_type,
+          ) -> ExternResult<ValidateCallbackResult> {
+            let rules: &[$crate::validation::UpdateRule<$crud_type>] = &[$($update_rule),*];
+            $crate::validation::validate_update_of_type(&action, &entry, rules)
+          }
+
+          /// Validate a delete of an entry of this type
+          pub fn [<validate_delete_ $i>](
+            action: Delete,
+          ) -> ExternResult<ValidateCallbackResult> {
+            let rules: &[$crate::validation::DeleteRule<$crud_type>] = &[$($delete_rule),*];
+            $crate::validation::validate_delete_of_type(&action, $entry_def, rules)
+          }
+
+          /// Validate a link which should point at an entry of this type,
+          /// such as the ones from the Path all entries of this type are linked off of,
+          /// or at a child Path of that Path
+          pub fn [<validate_create_link_ $i>](
+            target_address: AnyLinkableHash,
+            tag: LinkTag,
+          ) -> ExternResult<ValidateCallbackResult> {
+            $crate::validation::validate_link_to_type::<$crud_type>(target_address, &tag)
+          }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_delete_of_type, validate_link_to_type, DeleteRule};
+    use crate::crud::example::Example;
+    use crate::test_helpers::*;
+    use hdk::hash_path::path::DHT_PREFIX;
+    use hdk::prelude::*;
+
+    fn delete(author: AgentPubKey) -> Delete {
+        Delete {
+            author,
+            timestamp: timestamp(20),
+            action_seq: 5,
+            prev_action: action_hash(0),
+            deletes_address: action_hash(1),
+            deletes_entry_address: entry_hash(1),
+            weight: RateWeight::default(),
+        }
+    }
+
+    fn set_deleted_record(record: Record) {
+        let mut mock_hdk = MockHdkT::new();
+        mock_hdk
+            .expect_must_get_valid_record()
+            .returning(move |_| Ok(record.clone()));
+        set_hdk(mock_hdk);
+    }
+
+    #[test]
+    fn test_validate_delete_of_type() {
+        let rules = [DeleteRule::<Example>::AuthorOnly];
+        set_deleted_record(example_record(create_action(1, agent(1), 10), 1));
+        assert_eq!(
+            validate_delete_of_type(&delete(agent(1)), TestEntryType, &rules),
+            Ok(ValidateCallbackResult::Valid)
+        );
+        assert_eq!(
+            validate_delete_of_type(&delete(agent(2)), TestEntryType, &rules),
+            Ok(ValidateCallbackResult::Invalid(String::from(
+                "only the author of an entry can delete it"
+            )))
+        );
+
+        // an entry of another type, even one which deserializes into an Example
+        let Action::Create(mut create) = create_action(1, agent(1), 10).action().clone() else {
+            unreachable!()
+        };
+        create.entry_type = EntryType::App(AppEntryDef::new(
+            EntryDefIndex(1),
+            ZomeIndex(0),
+            EntryVisibility::Public,
+        ));
+        let other_type = signed(Action::Create(create), action_hash(1));
+        set_deleted_record(example_record(other_type, 1));
+        assert_eq!(
+            validate_delete_of_type(&delete(agent(1)), TestEntryType, &rules),
+            Ok(ValidateCallbackResult::Invalid(String::from(
+                "a delete must target an entry of this type"
+            )))
+        );
+    }
+
+    #[test]
+    fn test_validate_link_to_type() {
+        let example = Entry::try_from(Example { number: 1 }).unwrap();
+        let path = Entry::App(AppEntryBytes(
+            SerializedBytes::try_from(Path::from("example.2021")).unwrap(),
+        ));
+        let mut mock_hdk = MockHdkT::new();
+        mock_hdk.expect_must_get_entry().returning(move |input| {
+            match input.into_inner().get_raw_36()[0] {
+                1 => Ok(EntryHashed::with_pre_hashed(example.clone(), entry_hash(1))),
+                _ => Ok(EntryHashed::with_pre_hashed(path.clone(), entry_hash(2))),
+            }
+        });
+        set_hdk(mock_hdk);
+
+        let entry_tag = LinkTag::new(vec![]);
+        let path_tag = LinkTag::new(vec![DHT_PREFIX]);
+        assert_eq!(
+            validate_link_to_type::<Example>(entry_hash(1).into(), &entry_tag),
+            Ok(ValidateCallbackResult::Valid)
+        );
+        // the links between the Paths which entries are linked off of
+        assert_eq!(
+            validate_link_to_type::<Example>(entry_hash(2).into(), &path_tag),
+            Ok(ValidateCallbackResult::Valid)
+        );
+        assert_eq!(
+            validate_link_to_type::<Example>(entry_hash(2).into(), &entry_tag),
+            Ok(ValidateCallbackResult::Invalid(String::from(
+                "the link must target an entry of this type"
+            )))
+        );
+        assert_eq!(
+            validate_link_to_type::<Example>(action_hash(1).into(), &entry_tag),
+            Ok(ValidateCallbackResult::Invalid(String::from(
+                "the link must target an entry"
+            )))
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use hdk::prelude::*;
-use holo_hash::{EntryHashB64, ActionHashB64};
+use holo_hash::{AgentPubKeyB64, EntryHashB64, ActionHashB64};
 
 #[doc = "This data structure will be very broadly useful and represents
           how an entry should be serialized along with what metadata to
@@ -7,6 +7,9 @@ use holo_hash::{EntryHashB64, ActionHashB64};
           It is called `WireRecord` because it is how data looks passed
           'over the wire' or network."]
 /// It serializes with camelCase style replacement of underscores in object keys.
+/// `action_hash` is always the hash of the original Create action, while
+/// `latest_action_hash` is the hash of the action which produced the current `entry`.
+/// The optional fields default to `None` when missing, so older payloads still deserialize.
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct WireRecord<T> {
@@ -15,4 +18,24 @@ pub struct WireRecord<T> {
     pub entry: T,
     pub created_at: Timestamp,
     pub updated_at: Timestamp,
+    #[serde(default)]
+    pub created_by: Option<AgentPubKeyB64>,
+    #[serde(default)]
+    pub updated_by: Option<AgentPubKeyB64>,
+    #[serde(default)]
+    pub latest_action_hash: Option<ActionHashB64>,
+}
+
+/// A single version of an entry, as found in its history of updates.
+/// `action_hash` is the hash of the Create or Update action which produced this version,
+/// and `author` and `timestamp` are those of that action.
+/// It serializes with camelCase style replacement of underscores in object keys.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Revision<T> {
+    pub action_hash: ActionHashB64,
+    pub entry_hash: EntryHashB64,
+    pub entry: T,
+    pub author: AgentPubKeyB64,
+    pub timestamp: Timestamp,
 }
\ No newline at end of file